@@ -0,0 +1,587 @@
+//! Build a `.puz` file from a TOML or JSON spec.
+//!
+//! A spec is the puzzle as a constructor writes it: metadata, the solution
+//! grid one row per string, and clues keyed by number under `clues.across`
+//! and `clues.down`. Grid rows use a small cell notation:
+//!
+//! - `A`–`Z`, `0`–`9`: a square and its solution
+//! - `.`: a black square
+//! - a lowercase letter: a circled square
+//! - `[HEART]`: a rebus square (`[heart]` is a circled one)
+//!
+//! Problems in the spec are reported against the spec file, with a line and
+//! column for TOML, before the puzzle is assembled with the `Puzzle` builder
+//! and written with [`puz_parse::to_bytes`].
+
+use anyhow::{Context, Result};
+use clap::Args;
+use puz_parse::{ClueAnswer, ClueSet, Clues, Direction, Puzzle, Rebus};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use toml::de::DeValue;
+
+use crate::input;
+
+#[derive(Args)]
+pub(crate) struct BuildArgs {
+    /// the spec to build, in TOML or JSON (- for stdin)
+    #[arg(value_name = "SPEC")]
+    spec: PathBuf,
+
+    /// where to write the .puz file (- for stdout)
+    #[arg(short, long, value_name = "FILE")]
+    output: PathBuf,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Spec {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    author: String,
+    #[serde(default)]
+    copyright: String,
+    #[serde(default)]
+    notes: String,
+    version: Option<String>,
+    #[serde(default)]
+    diagramless: bool,
+    grid: Vec<String>,
+    #[serde(default)]
+    clues: SpecClues,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpecClues {
+    #[serde(default)]
+    across: BTreeMap<String, String>,
+    #[serde(default)]
+    down: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Toml,
+    Json,
+}
+
+impl Format {
+    /// From the spec's extension, or its contents: a TOML document can't
+    /// start with `{`.
+    fn of(path: &Path, text: &str) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Format::Json,
+            Some("toml") => Format::Toml,
+            _ if text.trim_start().starts_with('{') => Format::Json,
+            _ => Format::Toml,
+        }
+    }
+}
+
+/// One step of the path to a value in the spec.
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+/// A problem with the spec, at the value `path` leads to.
+struct SpecError {
+    path: Vec<Step>,
+    message: String,
+}
+
+impl SpecError {
+    fn new(path: Vec<Step>, message: impl Into<String>) -> Self {
+        SpecError {
+            path,
+            message: message.into(),
+        }
+    }
+}
+
+fn key(key: &str) -> Step {
+    Step::Key(key.to_string())
+}
+
+/// One square of a grid row.
+struct Cell {
+    /// The solution, uppercased; more than one letter for a rebus square.
+    /// Empty for a black square.
+    text: String,
+    circled: bool,
+}
+
+pub(crate) fn run(args: BuildArgs) -> Result<()> {
+    let data = input::read(&args.spec)?;
+    let name = args.spec.display();
+    let text = String::from_utf8(data).with_context(|| format!("{name} is not UTF-8"))?;
+    let format = Format::of(&args.spec, &text);
+    // Both parsers' messages include the line and column.
+    let spec: Spec = match format {
+        Format::Toml => toml::from_str(&text).with_context(|| format!("failed to read {name}"))?,
+        Format::Json => {
+            serde_json::from_str(&text).with_context(|| format!("failed to read {name}"))?
+        }
+    };
+
+    let puzzle = match assemble(spec) {
+        Ok(puzzle) => puzzle,
+        Err(errors) => {
+            for error in &errors {
+                eprintln!("{}", located(&name, &text, format, error));
+            }
+            anyhow::bail!("{name}: {} problem(s) in the spec", errors.len());
+        }
+    };
+    let bytes = puz_parse::to_bytes(&puzzle).with_context(|| format!("failed to build {name}"))?;
+    input::write(&args.output, bytes)
+}
+
+/// Build the puzzle a spec describes, or every problem found with it.
+fn assemble(spec: Spec) -> Result<Puzzle, Vec<SpecError>> {
+    let mut errors = Vec::new();
+    if spec.grid.is_empty() {
+        errors.push(SpecError::new(vec![key("grid")], "the grid has no rows"));
+        return Err(errors);
+    }
+
+    let mut rows = Vec::new();
+    for (r, row) in spec.grid.iter().enumerate() {
+        match parse_row(row) {
+            Ok(cells) => rows.push(cells),
+            Err(message) => errors.push(SpecError::new(
+                vec![key("grid"), Step::Index(r)],
+                format!("row {}: {message}", r + 1),
+            )),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let width = rows[0].len();
+    for (r, row) in rows.iter().enumerate().skip(1) {
+        if row.len() != width {
+            errors.push(SpecError::new(
+                vec![key("grid"), Step::Index(r)],
+                format!(
+                    "row {} is {} squares wide, but row 1 is {width}",
+                    r + 1,
+                    row.len()
+                ),
+            ));
+        }
+    }
+    if width > usize::from(u8::MAX) || rows.len() > usize::from(u8::MAX) {
+        errors.push(SpecError::new(
+            vec![key("grid")],
+            format!(
+                "the grid is {width} x {}; the most is 255 x 255",
+                rows.len()
+            ),
+        ));
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    // The solution grid holds a rebus square's first letter.
+    let solution: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| cell.text.chars().next().unwrap_or('.'))
+                .collect()
+        })
+        .collect();
+    let mut puzzle = Puzzle::new()
+        .title(spec.title)
+        .author(spec.author)
+        .copyright(spec.copyright)
+        .notes(spec.notes)
+        .diagramless(spec.diagramless)
+        .grid(&solution);
+    if let Some(version) = spec.version {
+        puzzle = puzzle.version(version);
+    }
+
+    // The grid alone decides the slots; each needs a clue from the spec.
+    let slots = puzzle.clue_answers();
+    let across = check_clues(&slots, Direction::Across, &spec.clues.across, &mut errors);
+    let down = check_clues(&slots, Direction::Down, &spec.clues.down, &mut errors);
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    puzzle = puzzle.clues(Clues::new(across, down));
+
+    if rows.iter().flatten().any(|cell| cell.circled) {
+        puzzle = puzzle.circles(
+            rows.iter()
+                .map(|row| row.iter().map(|cell| cell.circled).collect())
+                .collect(),
+        );
+    }
+    if rows
+        .iter()
+        .flatten()
+        .any(|cell| cell.text.chars().count() > 1)
+    {
+        match rebus(&rows) {
+            Some(rebus) => puzzle = puzzle.rebus(rebus),
+            None => {
+                return Err(vec![SpecError::new(
+                    vec![key("grid")],
+                    "more than 255 different rebus entries",
+                )]);
+            }
+        }
+    }
+    Ok(puzzle)
+}
+
+/// Split a grid row into squares.
+fn parse_row(row: &str) -> Result<Vec<Cell>, String> {
+    let mut cells = Vec::new();
+    let mut chars = row.chars();
+    while let Some(ch) = chars.next() {
+        let col = cells.len() + 1;
+        let cell = match ch {
+            '.' => Cell {
+                text: String::new(),
+                circled: false,
+            },
+            '[' => {
+                let rest = chars.as_str();
+                let Some(end) = rest.find(']') else {
+                    return Err(format!("unclosed [ at square {col}"));
+                };
+                let inner = &rest[..end];
+                chars = rest[end + 1..].chars();
+                if inner.is_empty() || !inner.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    return Err(format!(
+                        "the rebus at square {col} must be one or more letters and digits"
+                    ));
+                }
+                Cell {
+                    text: inner.to_ascii_uppercase(),
+                    circled: inner.chars().any(|c| c.is_ascii_lowercase()),
+                }
+            }
+            c if c.is_ascii_alphanumeric() => Cell {
+                text: c.to_ascii_uppercase().to_string(),
+                circled: c.is_ascii_lowercase(),
+            },
+            c => return Err(format!("unexpected {c:?} at square {col}")),
+        };
+        cells.push(cell);
+    }
+    if cells.is_empty() {
+        return Err("the row is empty".to_string());
+    }
+    Ok(cells)
+}
+
+/// Match the spec's clues for one direction against the grid's slots:
+/// every slot needs a clue, and every clue needs a slot.
+fn check_clues(
+    slots: &[ClueAnswer],
+    direction: Direction,
+    given: &BTreeMap<String, String>,
+    errors: &mut Vec<SpecError>,
+) -> ClueSet {
+    let name = match direction {
+        Direction::Across => "across",
+        Direction::Down => "down",
+    };
+    let path = |clue: Option<&str>| {
+        let mut path = vec![key("clues"), key(name)];
+        path.extend(clue.map(key));
+        path
+    };
+    let mut set = ClueSet::default();
+    let mut numbers = HashMap::new();
+    for (clue, text) in given {
+        match clue.parse::<u16>() {
+            Ok(number) => {
+                numbers.insert(number, clue.as_str());
+                set.set(number, text.as_str());
+            }
+            Err(_) => errors.push(SpecError::new(
+                path(Some(clue)),
+                format!("{clue:?} is not a clue number"),
+            )),
+        }
+    }
+    let slots: Vec<_> = slots.iter().filter(|s| s.direction == direction).collect();
+    for slot in &slots {
+        if !numbers.contains_key(&slot.number) {
+            errors.push(SpecError::new(
+                path(None),
+                format!(
+                    "missing {name} clue for {} (row {}, column {})",
+                    slot.number,
+                    slot.row + 1,
+                    slot.col + 1
+                ),
+            ));
+        }
+    }
+    let mut extra: Vec<_> = numbers
+        .iter()
+        .filter(|(number, _)| !slots.iter().any(|s| s.number == **number))
+        .collect();
+    extra.sort();
+    for (number, clue) in extra {
+        errors.push(SpecError::new(
+            path(Some(clue)),
+            format!("no {name} entry starts at {number}"),
+        ));
+    }
+    set
+}
+
+/// The rebus squares, each distinct text getting its own table key in the
+/// order it first appears. `None` if there are more than fit in a byte.
+fn rebus(rows: &[Vec<Cell>]) -> Option<Rebus> {
+    let mut keys: HashMap<&str, u8> = HashMap::new();
+    let mut grid = Vec::new();
+    for row in rows {
+        let mut values = Vec::new();
+        for cell in row {
+            if cell.text.chars().count() < 2 {
+                values.push(0);
+                continue;
+            }
+            let next = u8::try_from(keys.len()).ok()?;
+            let key = *keys.entry(&cell.text).or_insert(next);
+            // Grid values are 1-based; 0 means no rebus.
+            values.push(key.checked_add(1)?);
+        }
+        grid.push(values);
+    }
+    let table = keys
+        .into_iter()
+        .map(|(text, key)| (key, text.to_string()))
+        .collect();
+    Some(Rebus { grid, table })
+}
+
+/// `error` as `file:line:column: message` for a TOML spec, or `file: message`
+/// for JSON, which has no positions to offer.
+fn located(name: &impl fmt::Display, text: &str, format: Format, error: &SpecError) -> String {
+    let span = match format {
+        Format::Toml => toml_span(text, &error.path),
+        Format::Json => None,
+    };
+    match span {
+        Some(span) => {
+            let before = &text[..span.start];
+            let line = before.matches('\n').count() + 1;
+            let col = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+            format!("{name}:{line}:{col}: {}", error.message)
+        }
+        None => format!("{name}: {}", error.message),
+    }
+}
+
+/// Where `path` is in a TOML document: the deepest value along it that exists.
+fn toml_span(text: &str, path: &[Step]) -> Option<Range<usize>> {
+    let document = toml::de::DeTable::parse(text).ok()?;
+    let mut table = document.get_ref();
+    let mut value: Option<&toml::Spanned<DeValue>> = None;
+    for step in path {
+        let next = match (step, value.map(|v| v.get_ref())) {
+            (Step::Key(key), None) => table
+                .iter()
+                .find(|(k, _)| k.get_ref() == key)
+                .map(|(_, v)| v),
+            (Step::Key(key), Some(DeValue::Table(t))) => {
+                table = t;
+                table
+                    .iter()
+                    .find(|(k, _)| k.get_ref() == key)
+                    .map(|(_, v)| v)
+            }
+            (Step::Index(i), Some(DeValue::Array(items))) => items.get(*i),
+            _ => None,
+        };
+        match next {
+            Some(next) => value = Some(next),
+            None => break,
+        }
+    }
+    value.map(|v| v.span())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(text: &str) -> Spec {
+        toml::from_str(text).unwrap()
+    }
+
+    /// The messages `assemble` reports for a TOML spec, in order.
+    fn problems(text: &str) -> Vec<String> {
+        match assemble(spec(text)) {
+            Ok(_) => Vec::new(),
+            Err(errors) => errors.into_iter().map(|e| e.message).collect(),
+        }
+    }
+
+    const CLUED_2X2: &str = r#"
+grid = ["AB", "CD"]
+
+[clues.across]
+1 = "top"
+3 = "bottom"
+
+[clues.down]
+1 = "left"
+2 = "right"
+"#;
+
+    #[test]
+    fn test_parse_row_cells() {
+        let cells = parse_row("Ab.[HEART][cat]7").unwrap();
+        let seen: Vec<_> = cells.iter().map(|c| (c.text.as_str(), c.circled)).collect();
+        assert_eq!(
+            seen,
+            vec![
+                ("A", false),
+                ("B", true),
+                ("", false),
+                ("HEART", false),
+                ("CAT", true),
+                ("7", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_row_errors() {
+        let error = |row| parse_row(row).err().unwrap();
+        assert_eq!(error("AB#"), "unexpected '#' at square 3");
+        assert_eq!(error("A[HEART"), "unclosed [ at square 2");
+        assert_eq!(
+            error("[]"),
+            "the rebus at square 1 must be one or more letters and digits"
+        );
+        assert_eq!(
+            error("A.[HE ART]"),
+            "the rebus at square 3 must be one or more letters and digits"
+        );
+        assert_eq!(error(""), "the row is empty");
+    }
+
+    #[test]
+    fn test_assemble_builds_clued_grid() {
+        let puzzle = assemble(spec(CLUED_2X2)).ok().unwrap();
+        assert_eq!(puzzle.grid.solution, vec!["AB", "CD"]);
+        assert_eq!(puzzle.clues.across.get(3), Some("bottom"));
+        assert_eq!(puzzle.clues.down.get(2), Some("right"));
+        assert!(puzzle.extensions.circles.is_none());
+        assert!(puzzle.extensions.rebus.is_none());
+    }
+
+    #[test]
+    fn test_assemble_circles_and_rebus() {
+        let puzzle = assemble(spec(
+            r#"
+grid = ["a[HEART]", "[heart]D"]
+[clues.across]
+1 = "a"
+3 = "b"
+[clues.down]
+1 = "c"
+2 = "d"
+"#,
+        ))
+        .ok()
+        .unwrap();
+        assert_eq!(puzzle.grid.solution, vec!["AH", "HD"]);
+        assert_eq!(
+            puzzle.extensions.circles,
+            Some(vec![vec![true, false], vec![true, false]])
+        );
+        let rebus = puzzle.extensions.rebus.unwrap();
+        assert_eq!(rebus.grid, vec![vec![0, 1], vec![1, 0]]);
+        assert_eq!(rebus.table.get(&0).map(String::as_str), Some("HEART"));
+    }
+
+    #[test]
+    fn test_grid_errors() {
+        assert_eq!(problems("grid = []"), ["the grid has no rows"]);
+        assert_eq!(
+            problems(r#"grid = ["AB", "C#", "D?"]"#),
+            [
+                "row 2: unexpected '#' at square 2",
+                "row 3: unexpected '?' at square 2"
+            ]
+        );
+        assert_eq!(
+            problems(r#"grid = ["AB", "C", "DE"]"#),
+            ["row 2 is 1 squares wide, but row 1 is 2"]
+        );
+        let wide = format!("grid = [\"{}\"]", "A".repeat(256));
+        assert_eq!(
+            problems(&wide),
+            ["the grid is 256 x 1; the most is 255 x 255"]
+        );
+    }
+
+    #[test]
+    fn test_clue_errors() {
+        assert_eq!(
+            problems(r#"grid = ["AB", "CD"]"#),
+            [
+                "missing across clue for 1 (row 1, column 1)",
+                "missing across clue for 3 (row 2, column 1)",
+                "missing down clue for 1 (row 1, column 1)",
+                "missing down clue for 2 (row 1, column 2)",
+            ]
+        );
+        let extra = format!("{CLUED_2X2}5 = \"nowhere\"\nx = \"not a number\"\n");
+        assert_eq!(
+            problems(&extra),
+            ["\"x\" is not a clue number", "no down entry starts at 5"]
+        );
+    }
+
+    #[test]
+    fn test_located_points_at_toml_value() {
+        let text = "title = \"T\"\ngrid = [\n  \"AB\",\n  \"C#\",\n]\n";
+        let errors = assemble(spec(text)).err().unwrap();
+        assert_eq!(
+            located(&"spec.toml", text, Format::Toml, &errors[0]),
+            "spec.toml:4:3: row 2: unexpected '#' at square 2"
+        );
+
+        let text = format!("{CLUED_2X2}9 = \"nowhere\"\n");
+        let errors = assemble(spec(&text)).err().unwrap();
+        assert_eq!(
+            located(&"spec.toml", &text, Format::Toml, &errors[0]),
+            "spec.toml:11:5: no down entry starts at 9"
+        );
+    }
+
+    #[test]
+    fn test_located_without_a_position() {
+        let error = SpecError::new(vec![key("grid")], "the grid has no rows");
+        assert_eq!(
+            located(&"spec.json", "{\"grid\": []}", Format::Json, &error),
+            "spec.json: the grid has no rows"
+        );
+        // A path the document doesn't have points at its deepest existing
+        // value, or nowhere.
+        let error = SpecError::new(vec![key("clues"), key("across")], "missing");
+        assert_eq!(
+            located(&"spec.toml", "grid = []\n", Format::Toml, &error),
+            "spec.toml: missing"
+        );
+    }
+}
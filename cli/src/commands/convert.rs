@@ -0,0 +1,114 @@
+//! Convert a puzzle between the formats the library reads and writes.
+//!
+//! Every conversion goes through [`Puzzle`]: the input is parsed (or
+//! deserialized) into one, and the output is written from it. JSON is the
+//! serde form of the data model, the same object `puz parse --single` prints,
+//! so a puzzle can be dumped, edited as JSON, and written back as a `.puz`.
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use puz_parse::Puzzle;
+use std::path::{Path, PathBuf};
+
+use crate::input;
+
+#[derive(Args)]
+pub(crate) struct ConvertArgs {
+    /// the puzzle to convert (- for stdin)
+    #[arg(value_name = "IN")]
+    file: PathBuf,
+
+    /// where to write the converted puzzle (- for stdout)
+    #[arg(short, long, value_name = "OUT")]
+    output: PathBuf,
+
+    /// the input format (default: from IN's extension, or its contents)
+    #[arg(long, value_enum, value_name = "FMT")]
+    from: Option<Format>,
+
+    /// the output format (default: from OUT's extension)
+    #[arg(long, value_enum, value_name = "FMT")]
+    to: Option<Format>,
+
+    /// indent JSON output
+    #[arg(short, long)]
+    pretty: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// an Across Lite .puz file
+    Puz,
+    /// the puzzle as a JSON object
+    Json,
+}
+
+impl Format {
+    fn of(path: &Path) -> Option<Self> {
+        // An archive member's name ends in its own extension.
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "puz" => Some(Format::Puz),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+
+    /// Guess the format of data whose path doesn't say: JSON is an object,
+    /// and a `.puz` file starts with a binary checksum.
+    fn sniff(data: &[u8]) -> Self {
+        match data.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{') => Format::Json,
+            _ => Format::Puz,
+        }
+    }
+}
+
+pub(crate) fn run(args: ConvertArgs) -> Result<()> {
+    let to = match args.to.or_else(|| Format::of(&args.output)) {
+        Some(to) => to,
+        None => anyhow::bail!(
+            "can't tell the output format from {}; pass --to",
+            args.output.display()
+        ),
+    };
+
+    let data = input::read(&args.file)?;
+    let from = args
+        .from
+        .or_else(|| Format::of(&args.file))
+        .unwrap_or_else(|| Format::sniff(&data));
+    let name = args.file.display();
+    let puzzle = match from {
+        Format::Puz => {
+            let parsed = Puzzle::reader()
+                .from_bytes_verbose(&data)
+                .with_context(|| format!("failed to parse {name}"))?;
+            for warning in &parsed.warnings {
+                eprintln!("Warning in {name}: {warning}");
+            }
+            parsed.result
+        }
+        Format::Json => serde_json::from_slice::<Puzzle>(&data)
+            .with_context(|| format!("failed to read {name} as a JSON puzzle"))?,
+    };
+
+    let out = &args.output;
+    match to {
+        Format::Puz if input::is_stdio(out) => {
+            let bytes = puz_parse::to_bytes(&puzzle).context("failed to write the puzzle")?;
+            input::write(out, bytes)
+        }
+        Format::Puz => puz_parse::write_file(&puzzle, out)
+            .with_context(|| format!("failed to write {}", out.display())),
+        Format::Json => {
+            let mut json = if args.pretty {
+                serde_json::to_string_pretty(&puzzle)?
+            } else {
+                serde_json::to_string(&puzzle)?
+            };
+            json.push('\n');
+            input::write(out, json)
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Find duplicate and near-duplicate puzzles in a directory.
+//!
+//! Identical puzzles share a [`Puzzle::fingerprint`]; near-duplicates share
+//! only a [`Puzzle::solution_fingerprint`] (the same grid with edited clues or
+//! metadata).
+
+use anyhow::{Context, Result};
+use clap::Args;
+use puz_parse::Puzzle;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::commands::to_stdout;
+use crate::input;
+use crate::render;
+
+#[derive(Args)]
+pub(crate) struct DedupeArgs {
+    /// directory to scan recursively for .puz files and archives
+    #[arg(value_name = "DIR")]
+    dir: PathBuf,
+
+    /// write a CSV report of which files to keep, remove, or review
+    #[arg(long, value_name = "FILE")]
+    report: Option<PathBuf>,
+}
+
+struct Scanned {
+    name: String,
+    title: String,
+    fingerprint: u64,
+    solution: u64,
+}
+
+pub(crate) fn run(args: DedupeArgs) -> Result<()> {
+    let files = input::find(&args.dir)?;
+
+    let mut scanned = Vec::new();
+    for input in input::expand(files) {
+        let puzzle = match input
+            .read()
+            .map_err(anyhow::Error::from)
+            .and_then(|data| Ok(Puzzle::from_bytes(&data)?))
+        {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("skip {}: {e}", input.name());
+                continue;
+            }
+        };
+        scanned.push(Scanned {
+            name: input.name().into_owned(),
+            title: puzzle.info.title.clone(),
+            fingerprint: puzzle.fingerprint(),
+            solution: puzzle.solution_fingerprint(),
+        });
+    }
+
+    // Exact duplicates, in the order their first file was seen (files are
+    // sorted, so the first file in each group is the one kept).
+    let mut by_fingerprint: HashMap<u64, Vec<&Scanned>> = HashMap::new();
+    let mut order = Vec::new();
+    for s in &scanned {
+        let group = by_fingerprint.entry(s.fingerprint).or_default();
+        if group.is_empty() {
+            order.push(s.fingerprint);
+        }
+        group.push(s);
+    }
+    let groups: Vec<&Vec<&Scanned>> = order.iter().map(|f| &by_fingerprint[f]).collect();
+
+    // Near-duplicates: distinct puzzles (one representative each) whose grids
+    // match.
+    let mut by_solution: HashMap<u64, Vec<&Scanned>> = HashMap::new();
+    let mut solution_order = Vec::new();
+    for group in &groups {
+        let kept = group[0];
+        let near = by_solution.entry(kept.solution).or_default();
+        if near.is_empty() {
+            solution_order.push(kept.solution);
+        }
+        near.push(kept);
+    }
+    let near_groups: Vec<&Vec<&Scanned>> = solution_order
+        .iter()
+        .map(|f| &by_solution[f])
+        .filter(|g| g.len() > 1)
+        .collect();
+
+    let duplicate_groups: Vec<&Vec<&Scanned>> =
+        groups.iter().copied().filter(|g| g.len() > 1).collect();
+    to_stdout(|out| {
+        for (i, group) in duplicate_groups.iter().enumerate() {
+            writeln!(
+                out,
+                "{}",
+                render::bold(format!(
+                    "duplicates {} ({:016x}, {} files)",
+                    i + 1,
+                    group[0].fingerprint,
+                    group.len()
+                ))
+            )?;
+            writeln!(out, "  {}  {}", render::green("keep  "), group[0].name)?;
+            for s in &group[1..] {
+                writeln!(out, "  {}  {}", render::yellow("remove"), s.name)?;
+            }
+            writeln!(out)?;
+        }
+        for (i, group) in near_groups.iter().enumerate() {
+            writeln!(
+                out,
+                "{}",
+                render::bold(format!(
+                    "near-duplicates {} (same grid, different clues or metadata)",
+                    i + 1
+                ))
+            )?;
+            for s in group.iter() {
+                writeln!(out, "  {}  {}", s.name, render::dim(&s.title))?;
+            }
+            writeln!(out)?;
+        }
+
+        let removable: usize = duplicate_groups.iter().map(|g| g.len() - 1).sum();
+        writeln!(
+            out,
+            "{} puzzles: {} duplicate groups ({removable} removable files), {} near-duplicate groups",
+            scanned.len(),
+            duplicate_groups.len(),
+            near_groups.len()
+        )?;
+        Ok(())
+    })?;
+
+    if let Some(path) = &args.report {
+        write_report(path, &duplicate_groups, &near_groups)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// One CSV row per file in a duplicate or near-duplicate group.
+///
+/// `action` is `keep` or `remove` for exact duplicates and `review` for
+/// near-duplicates, which need a person to decide. A file kept from an exact
+/// group can also appear as `review` in a near-duplicate group.
+fn write_report(
+    path: &Path,
+    duplicate_groups: &[&Vec<&Scanned>],
+    near_groups: &[&Vec<&Scanned>],
+) -> Result<()> {
+    let mut out = csv::Writer::from_path(path)?;
+    out.write_record(["action", "group", "fingerprint", "file"])?;
+    let mut row = |action: &str, group: String, s: &Scanned| {
+        out.write_record([action, &group, &format!("{:016x}", s.fingerprint), &s.name])
+    };
+    for (i, group) in duplicate_groups.iter().enumerate() {
+        let id = format!("duplicates-{}", i + 1);
+        row("keep", id.clone(), group[0])?;
+        for s in &group[1..] {
+            row("remove", id.clone(), s)?;
+        }
+    }
+    for (i, group) in near_groups.iter().enumerate() {
+        let id = format!("near-{}", i + 1);
+        for s in group.iter() {
+            row("review", id.clone(), s)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
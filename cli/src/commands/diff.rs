@@ -0,0 +1,132 @@
+//! Show what changed between two versions of a puzzle.
+//!
+//! The comparison is [`Puzzle::diff`]; this module reads the two files and
+//! prints the result, as text for a person reviewing a revision or as JSON.
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use puz_parse::{Change, Direction, Extension, GridKind, Puzzle, PuzzleDiff};
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::commands::to_stdout;
+use crate::input;
+use crate::render;
+
+#[derive(Args)]
+pub(crate) struct DiffArgs {
+    /// the old version of the puzzle (- for stdin)
+    #[arg(value_name = "OLD")]
+    old: PathBuf,
+
+    /// the new version of the puzzle (- for stdin)
+    #[arg(value_name = "NEW")]
+    new: PathBuf,
+
+    /// output format
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// each change, grouped by what changed
+    Text,
+    /// the diff as one JSON object
+    Json,
+}
+
+pub(crate) fn run(args: DiffArgs) -> Result<()> {
+    if input::is_stdio(&args.old) && input::is_stdio(&args.new) {
+        anyhow::bail!("only one of OLD and NEW can be stdin");
+    }
+    let old = load(&args.old)?;
+    let new = load(&args.new)?;
+    let diff = old.diff(&new);
+
+    to_stdout(|out| match args.format {
+        Format::Json => Ok(writeln!(out, "{}", serde_json::to_string_pretty(&diff)?)?),
+        Format::Text if diff.is_empty() => Ok(writeln!(out, "no differences")?),
+        Format::Text => print_text(out, &diff),
+    })
+}
+
+fn load(path: &Path) -> Result<Puzzle> {
+    let data = input::read(path)?;
+    let parsed = Puzzle::reader()
+        .from_bytes_verbose(&data)
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+    for warning in &parsed.warnings {
+        eprintln!("Warning in {}: {warning}", path.display());
+    }
+    Ok(parsed.result)
+}
+
+fn print_text(out: &mut dyn Write, diff: &PuzzleDiff) -> Result<()> {
+    for field in &diff.info {
+        render::print_change(out, &field.field, Some(&field.old), Some(&field.new))?;
+    }
+
+    for (grid, heading) in [(GridKind::Solution, "solution"), (GridKind::Fill, "fill")] {
+        let cells: Vec<_> = diff.cells.iter().filter(|c| c.grid == grid).collect();
+        if cells.is_empty() {
+            continue;
+        }
+        writeln!(out, "{}", render::bold(format!("{heading} grid:")))?;
+        for cell in cells {
+            let square = |ch: Option<char>| ch.map_or("(none)".to_string(), |ch| ch.to_string());
+            writeln!(
+                out,
+                "  row {}, column {}: {} -> {}",
+                cell.row + 1,
+                cell.col + 1,
+                render::red(square(cell.old)),
+                render::green(square(cell.new)),
+            )?;
+        }
+    }
+
+    for clue in &diff.clues {
+        let letter = match clue.direction {
+            Direction::Across => 'A',
+            Direction::Down => 'D',
+        };
+        let label = match (&clue.old, &clue.new) {
+            (None, _) => format!("{}{letter} (added)", clue.number),
+            (_, None) => format!("{}{letter} (removed)", clue.number),
+            _ => format!("{}{letter}", clue.number),
+        };
+        render::print_change(out, &label, clue.old.as_deref(), clue.new.as_deref())?;
+    }
+
+    for ext in &diff.extensions {
+        let name = match ext.extension {
+            Extension::Rebus => "rebus",
+            Extension::Circles => "circles",
+            Extension::Given => "given",
+            Extension::Timer => "timer",
+        };
+        let change = match ext.change {
+            Change::Added => "added",
+            Change::Removed => "removed",
+            Change::Changed => "changed",
+        };
+        let squares: Vec<String> = ext
+            .cells
+            .iter()
+            .map(|(r, c)| format!("r{}c{}", r + 1, c + 1))
+            .collect();
+        if squares.is_empty() {
+            writeln!(out, "{} {change}", render::bold(format!("{name}:")))?;
+        } else {
+            writeln!(
+                out,
+                "{} {change} at {}",
+                render::bold(format!("{name}:")),
+                squares.join(" ")
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -7,39 +7,49 @@ use anyhow::{Context, Result};
 use clap::Subcommand;
 use comfy_table::{Cell, CellAlignment};
 use puz_parse::raw;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
+use crate::commands::to_stdout;
+use crate::input;
 use crate::render;
 
 #[derive(Subcommand)]
 pub(crate) enum DumpKind {
     /// show the header: dimensions, clue count, bitmask, version, scrambled tag
     Header {
-        /// the .puz file to read
+        /// the .puz file to read (- for stdin)
         #[arg(value_name = "FILE")]
         file: PathBuf,
     },
     /// show the solution and blank grids, and any black-square mismatches
     Grid {
-        /// the .puz file to read
+        /// the .puz file to read (- for stdin)
         #[arg(value_name = "FILE")]
         file: PathBuf,
     },
     /// show the title, author, copyright, clue list, and notes
     Strings {
-        /// the .puz file to read
+        /// the .puz file to read (- for stdin)
         #[arg(value_name = "FILE")]
         file: PathBuf,
     },
     /// compare the grid's clue numbering against the file's clue list
     Clues {
-        /// the .puz file to read
+        /// the .puz file to read (- for stdin)
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// show every byte, labeled with the header field, grid, string, or
+    /// section it belongs to
+    Hex {
+        /// the .puz file to read (- for stdin)
         #[arg(value_name = "FILE")]
         file: PathBuf,
     },
     /// print clues paired with their answers as a JSON array
     Answers {
-        /// the .puz file to read
+        /// the .puz file to read (- for stdin)
         #[arg(value_name = "FILE")]
         file: PathBuf,
 
@@ -50,25 +60,22 @@ pub(crate) enum DumpKind {
 }
 
 pub(crate) fn run(what: DumpKind) -> Result<()> {
-    match what {
-        DumpKind::Header { file } => dump_header(&file),
-        DumpKind::Grid { file } => dump_grid(&file),
-        DumpKind::Strings { file } => dump_strings(&file),
-        DumpKind::Clues { file } => dump_clues(&file),
-        DumpKind::Answers { file, pretty } => dump_answers(&file, pretty),
-    }
+    to_stdout(|out| match what {
+        DumpKind::Header { file } => dump_header(out, &file),
+        DumpKind::Grid { file } => dump_grid(out, &file),
+        DumpKind::Strings { file } => dump_strings(out, &file),
+        DumpKind::Clues { file } => dump_clues(out, &file),
+        DumpKind::Hex { file } => dump_hex(out, &file),
+        DumpKind::Answers { file, pretty } => dump_answers(out, &file, pretty),
+    })
 }
 
-fn read_file(path: &PathBuf) -> Result<Vec<u8>> {
-    std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))
-}
-
-fn dump_header(path: &PathBuf) -> Result<()> {
-    let data = read_file(path)?;
+fn dump_header(out: &mut dyn Write, path: &Path) -> Result<()> {
+    let data = input::read(path)?;
     let header = raw::read_header(&data)
         .with_context(|| format!("{} is too short for a .puz header", path.display()))?;
 
-    println!("{}", render::bold(path.display()));
+    writeln!(out, "{}", render::bold(path.display()))?;
     let mut table = render::borderless_table();
     table
         .add_row(vec!["size", &format!("{} bytes", data.len())])
@@ -81,35 +88,37 @@ fn dump_header(path: &PathBuf) -> Result<()> {
             "scrambled",
             &format!("0x{:04X}", header.scrambled_tag),
         ]);
-    println!("{table}");
+    writeln!(out, "{table}")?;
     Ok(())
 }
 
-fn dump_grid(path: &PathBuf) -> Result<()> {
-    let data = read_file(path)?;
+fn dump_grid(out: &mut dyn Write, path: &Path) -> Result<()> {
+    let data = input::read(path)?;
     let grids = raw::read_grids(&data)
         .with_context(|| format!("{} is too short for its declared grids", path.display()))?;
 
-    println!(
+    writeln!(
+        out,
         "{} {}",
         render::bold(path.display()),
         render::dim(format!("{}x{}", grids.width, grids.height))
-    );
+    )?;
 
-    render::print_grid("solution", &grids.solution);
-    render::print_grid("blank", &grids.blank);
+    render::print_grid(out, "solution", &grids.solution)?;
+    render::print_grid(out, "blank", &grids.blank)?;
 
     let mismatches = grids.black_square_mismatches();
     if mismatches.is_empty() {
-        println!("{}", render::green("black squares: consistent"));
+        writeln!(out, "{}", render::green("black squares: consistent"))?;
     } else {
-        println!(
+        writeln!(
+            out,
             "{}",
             render::yellow(format!(
                 "black-square mismatches: {} cell(s)",
                 mismatches.len()
             ))
-        );
+        )?;
         let mut table = render::bordered_table();
         table.set_header(vec!["row", "col", "solution", "blank"]);
         for m in mismatches.iter().take(16) {
@@ -120,30 +129,33 @@ fn dump_grid(path: &PathBuf) -> Result<()> {
                 Cell::new(render::byte_repr(m.blank)),
             ]);
         }
-        println!("{table}");
+        writeln!(out, "{table}")?;
         if mismatches.len() > 16 {
-            println!(
+            writeln!(
+                out,
                 "{}",
                 render::dim(format!("... {} more", mismatches.len() - 16))
-            );
+            )?;
         }
     }
 
-    println!(
+    writeln!(
+        out,
         "{} {}",
         render::dim("solution bytes:"),
         render::unique_bytes(&grids.solution)
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "{} {}",
         render::dim("blank bytes:"),
         render::unique_bytes(&grids.blank)
-    );
+    )?;
     Ok(())
 }
 
-fn dump_strings(path: &PathBuf) -> Result<()> {
-    let data = read_file(path)?;
+fn dump_strings(out: &mut dyn Write, path: &Path) -> Result<()> {
+    let data = input::read(path)?;
     let strings = raw::read_strings(&data)
         .with_context(|| format!("{} is too short for its string section", path.display()))?;
 
@@ -152,12 +164,13 @@ fn dump_strings(path: &PathBuf) -> Result<()> {
         .add_row(vec!["author", &strings.author])
         .add_row(vec!["copyright", &strings.copyright])
         .add_row(vec!["notes", &strings.notes]);
-    println!("{meta}");
+    writeln!(out, "{meta}")?;
 
-    println!(
+    writeln!(
+        out,
         "{}",
         render::bold(format!("--- {} clues ---", strings.clues.len()))
-    );
+    )?;
     let mut table = render::borderless_table();
     for (i, clue) in strings.clues.iter().enumerate() {
         table.add_row(vec![
@@ -165,12 +178,12 @@ fn dump_strings(path: &PathBuf) -> Result<()> {
             Cell::new(clue),
         ]);
     }
-    println!("{table}");
+    writeln!(out, "{table}")?;
     Ok(())
 }
 
-fn dump_clues(path: &PathBuf) -> Result<()> {
-    let data = read_file(path)?;
+fn dump_clues(out: &mut dyn Write, path: &Path) -> Result<()> {
+    let data = input::read(path)?;
     let header = raw::read_header(&data)
         .with_context(|| format!("{} is too short for a .puz header", path.display()))?;
     let grids = raw::read_grids(&data)
@@ -182,7 +195,7 @@ fn dump_clues(path: &PathBuf) -> Result<()> {
     let geometric = across + down;
     let declared = header.num_clues as usize;
 
-    println!("{}", render::bold(path.display()));
+    writeln!(out, "{}", render::bold(path.display()))?;
     let mut summary = render::borderless_table();
     summary
         .add_row(vec!["across slots", &across.to_string()])
@@ -197,17 +210,22 @@ fn dump_clues(path: &PathBuf) -> Result<()> {
             &placeholders.to_string(),
         ]);
     }
-    println!("{summary}");
+    writeln!(out, "{summary}")?;
 
     if geometric == declared {
-        println!("{}", render::green("geometry matches declared clue count"));
+        writeln!(
+            out,
+            "{}",
+            render::green("geometry matches declared clue count")
+        )?;
     } else {
-        println!(
+        writeln!(
+            out,
             "{}",
             render::yellow(format!(
                 "MISMATCH: geometry {geometric} vs declared {declared}"
             ))
-        );
+        )?;
     }
 
     // Numbered cells alongside the clue text the file provides in reading order.
@@ -240,27 +258,58 @@ fn dump_clues(path: &PathBuf) -> Result<()> {
             Cell::new(texts.join("  |  ")),
         ]);
     }
-    println!("{table}");
+    writeln!(out, "{table}")?;
 
     if let Some(s) = &strings
         && clue_idx < s.clues.len()
     {
-        println!(
+        writeln!(
+            out,
             "{}",
             render::yellow(format!(
                 "{} extra clue string(s) in file beyond geometric slots:",
                 s.clues.len() - clue_idx
             ))
-        );
+        )?;
         for (i, extra) in s.clues[clue_idx..].iter().enumerate() {
-            println!("  [{}] {}", clue_idx + i, render_clue(extra));
+            writeln!(out, "  [{}] {}", clue_idx + i, render_clue(extra))?;
+        }
+    }
+    Ok(())
+}
+
+fn dump_hex(out: &mut dyn Write, path: &Path) -> Result<()> {
+    let data = input::read(path)?;
+    writeln!(
+        out,
+        "{} {}",
+        render::bold(path.display()),
+        render::dim(format!("{} bytes", data.len()))
+    )?;
+
+    // Each region starts a new line, so a line never mixes two structures.
+    for region in raw::layout(&data) {
+        let bytes = &data[region.offset..region.offset + region.len];
+        for (i, chunk) in bytes.chunks(render::HEX_WIDTH).enumerate() {
+            let label = match i {
+                0 if region.unknown => format!("unknown ({} bytes)", region.len),
+                0 => region.label.clone(),
+                _ => String::new(),
+            };
+            let offset = region.offset + i * render::HEX_WIDTH;
+            let line = format!("{}  {label}", render::hex_line(offset, chunk));
+            if region.unknown {
+                writeln!(out, "{}", render::yellow(line.trim_end()))?;
+            } else {
+                writeln!(out, "{}", line.trim_end())?;
+            }
         }
     }
     Ok(())
 }
 
-fn dump_answers(path: &PathBuf, pretty: bool) -> Result<()> {
-    let data = read_file(path)?;
+fn dump_answers(out: &mut dyn Write, path: &Path, pretty: bool) -> Result<()> {
+    let data = input::read(path)?;
     // Parse leniently; the point is to read the grid and clues, so tolerate
     // checksum and other recoverable warnings (reported to stderr).
     let parsed = puz_parse::Puzzle::reader()
@@ -276,7 +325,7 @@ fn dump_answers(path: &PathBuf, pretty: bool) -> Result<()> {
     } else {
         serde_json::to_string(&entries)?
     };
-    println!("{json}");
+    writeln!(out, "{json}")?;
     Ok(())
 }
 
@@ -295,6 +344,9 @@ fn render_clue(c: &str) -> String {
     }
 }
 
-fn is_placeholder(c: &str) -> bool {
+/// Whether a clue is a stand-in rather than real text: empty, or just `-`.
+///
+/// Also used by `export --exclude-placeholders`.
+pub(crate) fn is_placeholder(c: &str) -> bool {
     c.trim().is_empty() || c.trim() == "-"
 }
@@ -0,0 +1,209 @@
+//! Edit a puzzle's metadata and clues in place.
+//!
+//! The file is parsed, the edits are applied to the `Puzzle`, and the file is
+//! written again with [`puz_parse::rewrite`], so every checksum is recomputed
+//! while sections the model doesn't hold (RUSR, unknown tags) are copied
+//! through. A file with clue strings beyond its grid's entries is refused
+//! rather than written without them. New text is checked against Windows-1252
+//! before the file is even read, since a `.puz` can't store anything else.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use puz_parse::encoding::encode_windows_1252;
+use puz_parse::{Direction, Puzzle};
+use std::path::{Path, PathBuf};
+
+use crate::commands::to_stdout;
+use crate::input;
+use crate::render;
+
+#[derive(Args)]
+pub(crate) struct EditArgs {
+    /// the .puz file to edit
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+
+    /// set the title
+    #[arg(long)]
+    title: Option<String>,
+
+    /// set the author
+    #[arg(long)]
+    author: Option<String>,
+
+    /// set the copyright line
+    #[arg(long)]
+    copyright: Option<String>,
+
+    /// set the notes
+    #[arg(long)]
+    notes: Option<String>,
+
+    /// set a clue's text, e.g. --clue 12A "New clue"; repeatable
+    #[arg(long, num_args = 2, value_names = ["CLUE", "TEXT"])]
+    clue: Vec<String>,
+
+    /// show what would change without writing the file
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// One field to change.
+struct Edit {
+    /// What the field is called in the diff, e.g. `title` or `12A`.
+    label: String,
+    target: Target,
+    text: String,
+}
+
+enum Target {
+    Title,
+    Author,
+    Copyright,
+    Notes,
+    Clue(u16, Direction),
+}
+
+impl Target {
+    fn get<'a>(&self, puzzle: &'a Puzzle) -> Option<&'a str> {
+        let info = &puzzle.info;
+        match *self {
+            Target::Title => Some(&info.title),
+            Target::Author => Some(&info.author),
+            Target::Copyright => Some(&info.copyright),
+            Target::Notes => Some(&info.notes),
+            Target::Clue(number, Direction::Across) => puzzle.clues.across.get(number),
+            Target::Clue(number, Direction::Down) => puzzle.clues.down.get(number),
+        }
+    }
+
+    fn set(&self, puzzle: &mut Puzzle, text: String) {
+        let info = &mut puzzle.info;
+        match *self {
+            Target::Title => info.title = text,
+            Target::Author => info.author = text,
+            Target::Copyright => info.copyright = text,
+            Target::Notes => info.notes = text,
+            Target::Clue(number, Direction::Across) => {
+                puzzle.clues.across.set(number, text);
+            }
+            Target::Clue(number, Direction::Down) => {
+                puzzle.clues.down.set(number, text);
+            }
+        }
+    }
+}
+
+pub(crate) fn run(args: EditArgs) -> Result<()> {
+    let edits = edits(&args)?;
+    if edits.is_empty() {
+        anyhow::bail!("nothing to edit; pass --title, --author, --copyright, --notes, or --clue");
+    }
+    for edit in &edits {
+        encode_windows_1252(&edit.text, &edit.label)
+            .with_context(|| format!("can't set {}", edit.label))?;
+    }
+    if !args.dry_run && !input::is_disk_path(&args.file) {
+        anyhow::bail!(
+            "{} is not a file on disk; only --dry-run can read it",
+            args.file.display()
+        );
+    }
+
+    let data = input::read(&args.file)?;
+    let parsed = Puzzle::reader()
+        .from_bytes_verbose(&data)
+        .with_context(|| format!("failed to parse {}", args.file.display()))?;
+    for warning in &parsed.warnings {
+        eprintln!("Warning in {}: {warning}", args.file.display());
+    }
+    let mut puzzle = parsed.result;
+
+    let mut changed = false;
+    for edit in edits {
+        let Some(old) = edit.target.get(&puzzle) else {
+            anyhow::bail!("{} has no clue {}", args.file.display(), edit.label);
+        };
+        if old == edit.text {
+            continue;
+        }
+        changed = true;
+        if args.dry_run {
+            to_stdout(|out| {
+                Ok(render::print_change(
+                    out,
+                    &edit.label,
+                    Some(old),
+                    Some(&edit.text),
+                )?)
+            })?;
+        }
+        edit.target.set(&mut puzzle, edit.text);
+    }
+
+    if !changed {
+        eprintln!("{}: no changes", args.file.display());
+    } else if !args.dry_run {
+        let bytes = puz_parse::rewrite(&data, &puzzle)
+            .with_context(|| format!("failed to write {}", args.file.display()))?;
+        replace(&args.file, &bytes)
+            .with_context(|| format!("failed to write {}", args.file.display()))?;
+    }
+    Ok(())
+}
+
+/// The requested edits, in the order of the options' help.
+fn edits(args: &EditArgs) -> Result<Vec<Edit>> {
+    let mut edits = Vec::new();
+    for (label, target, text) in [
+        ("title", Target::Title, &args.title),
+        ("author", Target::Author, &args.author),
+        ("copyright", Target::Copyright, &args.copyright),
+        ("notes", Target::Notes, &args.notes),
+    ] {
+        if let Some(text) = text {
+            edits.push(Edit {
+                label: label.to_string(),
+                target,
+                text: text.clone(),
+            });
+        }
+    }
+    for pair in args.clue.chunks_exact(2) {
+        let (number, direction) = parse_clue(&pair[0])?;
+        let letter = match direction {
+            Direction::Across => 'A',
+            Direction::Down => 'D',
+        };
+        edits.push(Edit {
+            label: format!("{number}{letter}"),
+            target: Target::Clue(number, direction),
+            text: pair[1].clone(),
+        });
+    }
+    Ok(edits)
+}
+
+/// Parse a clue reference like `12A` or `3d`.
+fn parse_clue(s: &str) -> Result<(u16, Direction)> {
+    let invalid = || anyhow::anyhow!("invalid clue {s:?}: expected a number then A or D, like 12A");
+    let Some((end, letter)) = s.char_indices().next_back() else {
+        return Err(invalid());
+    };
+    let direction = match letter {
+        'A' | 'a' => Direction::Across,
+        'D' | 'd' => Direction::Down,
+        _ => return Err(invalid()),
+    };
+    let number = s[..end].parse().map_err(|_| invalid())?;
+    Ok((number, direction))
+}
+
+/// Write `data` over the file at `path` by renaming a finished copy over it,
+/// so a failed write leaves the original intact.
+fn replace(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, data)?;
+    std::fs::rename(&tmp, path)
+}
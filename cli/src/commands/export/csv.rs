@@ -0,0 +1,49 @@
+//! CSV output: a header row, then one record per clue/answer pair.
+//!
+//! Clues routinely contain commas and quotes; the `csv` crate quotes those
+//! fields (doubling embedded quotes) per RFC 4180.
+
+use anyhow::Result;
+use std::io::Write;
+
+use super::{Field, Sink, Source, Value};
+
+pub(super) struct CsvSink {
+    // `csv::Writer` buffers internally.
+    out: csv::Writer<Box<dyn Write>>,
+    fields: Vec<Field>,
+}
+
+impl CsvSink {
+    pub(super) fn new(out: Box<dyn Write>, fields: Vec<Field>) -> Result<Self> {
+        let mut out = csv::Writer::from_writer(out);
+        out.write_record(fields.iter().map(|f| f.name()))
+            .map_err(std::io::Error::from)?;
+        Ok(CsvSink { out, fields })
+    }
+}
+
+impl Sink for CsvSink {
+    fn add(&mut self, source: &Source<'_>) -> Result<usize> {
+        for entry in source.entries {
+            for &field in &self.fields {
+                // Surface I/O failures as `io::Error` so a closed pipe is
+                // recognized and treated as a normal stop.
+                match source.value(entry, field) {
+                    Value::Text(text) => self.out.write_field(text),
+                    Value::Integer(n) => self.out.write_field(n.to_string()),
+                }
+                .map_err(std::io::Error::from)?;
+            }
+            self.out
+                .write_record(None::<&[u8]>)
+                .map_err(std::io::Error::from)?;
+        }
+        Ok(source.entries.len())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
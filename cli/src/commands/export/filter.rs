@@ -0,0 +1,179 @@
+//! Puzzle- and entry-level filters for `puz export`, applied before any sink
+//! sees the data.
+
+use anyhow::{Context, Result};
+use puz_parse::{ClueAnswer, Direction, Puzzle};
+use regex::Regex;
+
+use super::ExportArgs;
+use crate::commands::dump::is_placeholder;
+
+/// The default filter keeps everything.
+#[derive(Default)]
+pub(super) struct Filter {
+    /// Lowercased, for a case-insensitive substring match.
+    author: Option<String>,
+    title: Option<Regex>,
+    answer: Option<Regex>,
+    min_length: Option<usize>,
+    direction: Option<Direction>,
+    exclude_placeholders: bool,
+}
+
+impl Filter {
+    pub(super) fn new(args: &ExportArgs) -> Result<Self> {
+        let compile = |pattern: &Option<String>, flag: &str| {
+            pattern
+                .as_deref()
+                .map(|p| Regex::new(p).with_context(|| format!("invalid {flag} pattern")))
+                .transpose()
+        };
+        Ok(Filter {
+            author: args.author.as_deref().map(str::to_lowercase),
+            title: compile(&args.title_match, "--title-match")?,
+            answer: compile(&args.answer_match, "--answer-match")?,
+            min_length: args.min_length,
+            direction: args.direction.map(Direction::from),
+            exclude_placeholders: args.exclude_placeholders,
+        })
+    }
+
+    /// Whether the filter keeps everything, as the default does.
+    pub(super) fn is_empty(&self) -> bool {
+        self.author.is_none()
+            && self.title.is_none()
+            && self.answer.is_none()
+            && self.min_length.is_none()
+            && self.direction.is_none()
+            && !self.exclude_placeholders
+    }
+
+    /// Whether any of a puzzle's entries can be exported at all.
+    pub(super) fn keeps_puzzle(&self, puzzle: &Puzzle) -> bool {
+        if let Some(author) = &self.author
+            && !puzzle.info.author.to_lowercase().contains(author)
+        {
+            return false;
+        }
+        if let Some(title) = &self.title
+            && !title.is_match(&puzzle.info.title)
+        {
+            return false;
+        }
+        true
+    }
+
+    pub(super) fn keeps_entry(&self, entry: &ClueAnswer) -> bool {
+        if self.direction.is_some_and(|d| d != entry.direction) {
+            return false;
+        }
+        if self
+            .min_length
+            .is_some_and(|min| entry.answer.chars().count() < min)
+        {
+            return false;
+        }
+        if self.exclude_placeholders && is_placeholder(&entry.clue) {
+            return false;
+        }
+        if let Some(answer) = &self.answer
+            && !answer.is_match(&entry.answer)
+        {
+            return false;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Across entries 1, 3, and 5; down entries 1, 2, and 4. 5 Across has a
+    /// placeholder clue.
+    fn sample() -> Puzzle {
+        let mut p = Puzzle::new()
+            .title("Monday Themeless")
+            .author("Jane Doe")
+            .grid(["AB.", "CDE", ".FG"]);
+        for (n, clue) in [(1, "one"), (3, "three"), (5, "-")] {
+            p.clues.across.set(n, clue);
+        }
+        for (n, clue) in [(1, "one down"), (2, "two down"), (4, "four down")] {
+            p.clues.down.set(n, clue);
+        }
+        p
+    }
+
+    /// The entries `filter` keeps, as `1A`-style labels.
+    fn kept(filter: &Filter) -> Vec<String> {
+        sample()
+            .clue_answers()
+            .into_iter()
+            .filter(|e| filter.keeps_entry(e))
+            .map(|e| {
+                let letter = match e.direction {
+                    Direction::Across => 'A',
+                    Direction::Down => 'D',
+                };
+                format!("{}{letter}", e.number)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_default_keeps_everything() {
+        let filter = Filter::default();
+        assert!(filter.is_empty());
+        assert!(filter.keeps_puzzle(&sample()));
+        assert_eq!(kept(&filter), ["1A", "1D", "2D", "3A", "4D", "5A"]);
+    }
+
+    #[test]
+    fn test_keeps_puzzle() {
+        let author = |a: &str| Filter {
+            author: Some(a.to_string()),
+            ..Filter::default()
+        };
+        // The author is lowercased by `Filter::new`; the puzzle's is not.
+        assert!(author("jane").keeps_puzzle(&sample()));
+        assert!(!author("john").keeps_puzzle(&sample()));
+
+        let title = |t: &str| Filter {
+            title: Some(Regex::new(t).unwrap()),
+            ..Filter::default()
+        };
+        assert!(title("^Monday").keeps_puzzle(&sample()));
+        assert!(!title("^Themeless").keeps_puzzle(&sample()));
+        assert!(!title("^Monday").is_empty());
+    }
+
+    #[test]
+    fn test_keeps_entry() {
+        let down = Filter {
+            direction: Some(Direction::Down),
+            ..Filter::default()
+        };
+        assert_eq!(kept(&down), ["1D", "2D", "4D"]);
+
+        let long = Filter {
+            min_length: Some(3),
+            ..Filter::default()
+        };
+        assert_eq!(kept(&long), ["2D", "3A"]);
+
+        let real_clues = Filter {
+            exclude_placeholders: true,
+            ..Filter::default()
+        };
+        assert_eq!(kept(&real_clues), ["1A", "1D", "2D", "3A", "4D"]);
+
+        // Every condition has to hold.
+        let both = Filter {
+            answer: Some(Regex::new("^[A-C]").unwrap()),
+            direction: Some(Direction::Across),
+            ..Filter::default()
+        };
+        assert_eq!(kept(&both), ["1A", "3A"]);
+    }
+}
@@ -0,0 +1,62 @@
+//! JSON Lines output: one object per clue/answer pair.
+
+use anyhow::Result;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::io::{BufWriter, Write};
+
+use super::{Field, Sink, Source, Value};
+
+pub(super) struct JsonlSink {
+    out: BufWriter<Box<dyn Write>>,
+    fields: Vec<Field>,
+}
+
+impl JsonlSink {
+    pub(super) fn new(out: Box<dyn Write>, fields: Vec<Field>) -> Self {
+        // Buffer: this can emit millions of lines across a large corpus.
+        JsonlSink {
+            out: BufWriter::new(out),
+            fields,
+        }
+    }
+}
+
+/// A row serialized as an object with the selected fields, in order.
+struct Object<'s, 'a> {
+    source: &'s Source<'a>,
+    entry: &'a puz_parse::ClueAnswer,
+    fields: &'s [Field],
+}
+
+impl Serialize for Object<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for &field in self.fields {
+            match self.source.value(self.entry, field) {
+                Value::Text(text) => map.serialize_entry(field.name(), text)?,
+                Value::Integer(n) => map.serialize_entry(field.name(), &n)?,
+            }
+        }
+        map.end()
+    }
+}
+
+impl Sink for JsonlSink {
+    fn add(&mut self, source: &Source<'_>) -> Result<usize> {
+        for entry in source.entries {
+            let object = Object {
+                source,
+                entry,
+                fields: &self.fields,
+            };
+            serde_json::to_writer(&mut self.out, &object).map_err(std::io::Error::from)?;
+            self.out.write_all(b"\n")?;
+        }
+        Ok(source.entries.len())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
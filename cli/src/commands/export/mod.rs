@@ -0,0 +1,411 @@
+//! Export clue/answer data from a directory of `.puz` files, as JSON Lines,
+//! CSV, Parquet, or a SQLite database.
+//!
+//! The directory walk, parsing, and filtering live here; each output format is
+//! a [`Sink`] that receives one parsed puzzle (and its kept entries) at a time.
+
+mod csv;
+mod filter;
+mod jsonl;
+mod parquet;
+mod sqlite;
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use puz_parse::{ClueAnswer, Direction, Puzzle};
+use std::fs::File;
+use std::io::Write;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+
+use crate::commands::{default_jobs, is_broken_pipe, parallel_in_order};
+use crate::input::{self, Input};
+use filter::Filter;
+
+#[derive(Args)]
+pub(crate) struct ExportArgs {
+    /// directory to scan recursively for .puz files and archives
+    #[arg(value_name = "DIR")]
+    dir: PathBuf,
+
+    /// output format
+    #[arg(long, value_enum, default_value_t = Format::Jsonl)]
+    format: Format,
+
+    /// write output to a file instead of stdout (required for parquet and sqlite)
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// with --format sqlite, replace FILE even if it isn't a database puz wrote
+    #[arg(long)]
+    force: bool,
+
+    /// add width, height, length, and version columns (sqlite always has them)
+    #[arg(long)]
+    extra_columns: bool,
+
+    /// columns to write, in order (comma-separated; not for sqlite)
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        value_name = "FIELDS",
+        conflicts_with = "extra_columns"
+    )]
+    fields: Option<Vec<Field>>,
+
+    /// only puzzles whose author contains this text (case-insensitive)
+    #[arg(long, value_name = "TEXT")]
+    author: Option<String>,
+
+    /// only puzzles whose title matches this regex
+    #[arg(long, value_name = "REGEX")]
+    title_match: Option<String>,
+
+    /// only entries whose answer matches this regex
+    #[arg(long, value_name = "REGEX")]
+    answer_match: Option<String>,
+
+    /// only entries whose answer is at least this many squares long
+    #[arg(long, value_name = "N")]
+    min_length: Option<usize>,
+
+    /// only entries in this direction
+    #[arg(long, value_enum)]
+    direction: Option<DirectionArg>,
+
+    /// skip entries whose clue is empty or just "-"
+    #[arg(long)]
+    exclude_placeholders: bool,
+
+    /// files to parse in parallel (default: the number of CPUs)
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<NonZeroUsize>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// one JSON object per clue/answer pair
+    Jsonl,
+    /// comma-separated values with a header row
+    Csv,
+    /// an Apache Parquet file, written in row groups
+    Parquet,
+    /// a SQLite database with puzzles, entries, rebus, and circles tables
+    Sqlite,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DirectionArg {
+    Across,
+    Down,
+}
+
+impl From<DirectionArg> for Direction {
+    fn from(d: DirectionArg) -> Self {
+        match d {
+            DirectionArg::Across => Direction::Across,
+            DirectionArg::Down => Direction::Down,
+        }
+    }
+}
+
+/// A column of the row-oriented formats (JSON Lines, CSV, Parquet).
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Field {
+    File,
+    Title,
+    Author,
+    Direction,
+    Number,
+    Clue,
+    Answer,
+    Width,
+    Height,
+    /// the answer's length in squares
+    Length,
+    Version,
+}
+
+impl Field {
+    /// Written when neither `--fields` nor `--extra-columns` is given.
+    ///
+    /// Outlet and date are intentionally absent: they are inconsistent inside
+    /// `.puz` files and usually live in the directory layout instead. `file`
+    /// is included so a consumer can derive those downstream.
+    const DEFAULT: [Field; 7] = [
+        Field::File,
+        Field::Title,
+        Field::Author,
+        Field::Direction,
+        Field::Number,
+        Field::Clue,
+        Field::Answer,
+    ];
+
+    /// Appended to [`Field::DEFAULT`] by `--extra-columns`.
+    const EXTRA: [Field; 4] = [Field::Width, Field::Height, Field::Length, Field::Version];
+
+    fn name(self) -> &'static str {
+        match self {
+            Field::File => "file",
+            Field::Title => "title",
+            Field::Author => "author",
+            Field::Direction => "direction",
+            Field::Number => "number",
+            Field::Clue => "clue",
+            Field::Answer => "answer",
+            Field::Width => "width",
+            Field::Height => "height",
+            Field::Length => "length",
+            Field::Version => "version",
+        }
+    }
+
+    fn is_integer(self) -> bool {
+        matches!(
+            self,
+            Field::Number | Field::Width | Field::Height | Field::Length
+        )
+    }
+}
+
+/// One parsed puzzle, handed to a [`Sink`].
+struct Source<'a> {
+    /// The file's path, as given on the command line plus the walk below it.
+    file: &'a str,
+    /// The raw file bytes, for content hashing.
+    data: &'a [u8],
+    puzzle: &'a Puzzle,
+    /// The puzzle's clue/answer pairs that passed the filters.
+    entries: &'a [ClueAnswer],
+}
+
+/// One field's value in a row.
+enum Value<'a> {
+    Text(&'a str),
+    Integer(i64),
+}
+
+impl<'a> Source<'a> {
+    fn value(&self, entry: &'a ClueAnswer, field: Field) -> Value<'a> {
+        let info = &self.puzzle.info;
+        match field {
+            Field::File => Value::Text(self.file),
+            Field::Title => Value::Text(&info.title),
+            Field::Author => Value::Text(&info.author),
+            Field::Direction => Value::Text(direction_name(entry.direction)),
+            Field::Number => Value::Integer(entry.number.into()),
+            Field::Clue => Value::Text(&entry.clue),
+            Field::Answer => Value::Text(&entry.answer),
+            Field::Width => Value::Integer(info.width.into()),
+            Field::Height => Value::Integer(info.height.into()),
+            Field::Length => Value::Integer(entry.answer.chars().count() as i64),
+            Field::Version => Value::Text(&info.version),
+        }
+    }
+}
+
+pub(crate) fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Across => "across",
+        Direction::Down => "down",
+    }
+}
+
+/// An export destination.
+trait Sink {
+    /// Write one puzzle, returning how many clue/answer rows it produced.
+    fn add(&mut self, source: &Source<'_>) -> Result<usize>;
+
+    /// Flush and close the output once every file has been added.
+    fn finish(self: Box<Self>) -> Result<()>;
+}
+
+pub(crate) fn run(args: ExportArgs) -> Result<()> {
+    let filter = Filter::new(&args)?;
+    let fields = match (&args.fields, args.extra_columns) {
+        (Some(_), _) if matches!(args.format, Format::Sqlite) => {
+            anyhow::bail!("--fields does not apply to --format sqlite")
+        }
+        (Some(fields), _) => fields.clone(),
+        (None, false) => Field::DEFAULT.to_vec(),
+        (None, true) => [Field::DEFAULT.as_slice(), &Field::EXTRA].concat(),
+    };
+
+    let files = input::find(&args.dir)?;
+
+    let sink: Box<dyn Sink> = match args.format {
+        Format::Jsonl => Box::new(jsonl::JsonlSink::new(
+            open_output(args.output.as_deref())?,
+            fields,
+        )),
+        Format::Csv => Box::new(csv::CsvSink::new(
+            open_output(args.output.as_deref())?,
+            fields,
+        )?),
+        Format::Parquet => {
+            let Some(path) = args.output.as_deref().filter(|p| !input::is_stdio(p)) else {
+                anyhow::bail!("--format parquet needs an output path (-o FILE)");
+            };
+            let file = File::create(path)
+                .with_context(|| format!("failed to create {}", path.display()))?;
+            Box::new(parquet::ParquetSink::new(file, fields)?)
+        }
+        Format::Sqlite => {
+            let Some(path) = args.output.as_deref().filter(|p| !input::is_stdio(p)) else {
+                anyhow::bail!("--format sqlite needs a database path (-o FILE)");
+            };
+            if !args.force && !sqlite::replaceable(path) {
+                anyhow::bail!(
+                    "{} exists and isn't a database puz wrote; pass --force to replace it",
+                    path.display()
+                );
+            }
+            Box::new(
+                sqlite::SqliteSink::create(
+                    path,
+                    filter
+                        .is_empty()
+                        .then_some((args.dir.as_path(), files.as_slice())),
+                )
+                .with_context(|| format!("failed to create {}", path.display()))?,
+            )
+        }
+    };
+
+    let jobs = args.jobs.map_or_else(default_jobs, NonZeroUsize::get);
+    export_files(files, jobs, &filter, sink)
+}
+
+/// Build the SQLite database `export --format sqlite` would, with no filters.
+///
+/// `search --index` uses this to build its index on first use. A database
+/// that fails partway is removed rather than left to be reused.
+pub(crate) fn build_sqlite(dir: &Path, db: &Path) -> Result<()> {
+    let files = input::find(dir)?;
+    if !sqlite::replaceable(db) {
+        anyhow::bail!(
+            "{} exists and isn't a database puz wrote; remove it or choose another path",
+            db.display()
+        );
+    }
+    let sink = sqlite::SqliteSink::create(db, Some((dir, &files)))
+        .with_context(|| format!("failed to create {}", db.display()))?;
+    let built = export_files(files, default_jobs(), &Filter::default(), Box::new(sink));
+    if built.is_err() {
+        let _ = std::fs::remove_file(db);
+    }
+    built
+}
+
+/// Whether the SQLite database at `db` was built from `dir` as it is now:
+/// the same directory, with no file added, removed, or modified since.
+pub(crate) fn sqlite_is_current(db: &Path, dir: &Path) -> Result<bool> {
+    let files = input::find(dir)?;
+    sqlite::is_current(db, dir, &files).with_context(|| format!("failed to read {}", db.display()))
+}
+
+/// A file as prepared on a worker thread, ready for the sink.
+enum Loaded {
+    /// Unreadable or unparseable, with the reason.
+    Skipped(String),
+    /// Parsed, but excluded by the puzzle-level filters.
+    Filtered,
+    Kept {
+        data: Vec<u8>,
+        puzzle: Box<Puzzle>,
+        entries: Vec<ClueAnswer>,
+    },
+}
+
+fn load(input: &Input, filter: &Filter) -> Loaded {
+    let data = match input.read() {
+        Ok(d) => d.into_owned(),
+        Err(e) => return Loaded::Skipped(e.to_string()),
+    };
+    // Parse leniently; warnings (checksums, extra clues, ...) don't block
+    // extracting clues and answers.
+    let puzzle = match Puzzle::from_bytes(&data) {
+        Ok(p) => p,
+        Err(e) => return Loaded::Skipped(e.to_string()),
+    };
+    if !filter.keeps_puzzle(&puzzle) {
+        return Loaded::Filtered;
+    }
+    let mut entries = puzzle.clue_answers();
+    entries.retain(|e| filter.keeps_entry(e));
+    Loaded::Kept {
+        data,
+        puzzle: Box::new(puzzle),
+        entries,
+    }
+}
+
+/// Parse the files on `jobs` threads and feed those that pass `filter` to
+/// `sink`, in file order.
+fn export_files(
+    files: Vec<PathBuf>,
+    jobs: usize,
+    filter: &Filter,
+    mut sink: Box<dyn Sink>,
+) -> Result<()> {
+    let mut exported = 0usize;
+    let mut scanned = 0usize;
+    let mut skipped = 0usize;
+    let result = parallel_in_order(
+        input::expand(files),
+        jobs,
+        |input| load(input, filter),
+        |input, loaded| {
+            scanned += 1;
+            let Loaded::Kept {
+                data,
+                puzzle,
+                entries,
+            } = loaded
+            else {
+                if let Loaded::Skipped(reason) = loaded {
+                    eprintln!("skip {}: {reason}", input.name());
+                    skipped += 1;
+                }
+                return Ok(());
+            };
+            let file = input.name();
+            let source = Source {
+                file: &file,
+                data: &data,
+                puzzle: &puzzle,
+                entries: &entries,
+            };
+            match sink.add(&source) {
+                Ok(rows) => exported += rows,
+                Err(e) if is_broken_pipe(&e) => return Err(e),
+                Err(e) => return Err(e.context(format!("failed to export {}", input.name()))),
+            }
+            Ok(())
+        },
+    );
+
+    // A closed downstream pipe (e.g. `puz export ... | head`) is a normal way
+    // to stop; exit cleanly instead of erroring.
+    match result.and_then(|()| sink.finish()) {
+        Err(e) if is_broken_pipe(&e) => return Ok(()),
+        result => result?,
+    }
+    eprintln!(
+        "exported {exported} clue/answer rows from {} files ({skipped} skipped)",
+        scanned - skipped
+    );
+    Ok(())
+}
+
+/// The file at `path`, or stdout if there is none or it is `-`.
+fn open_output(path: Option<&Path>) -> Result<Box<dyn Write>> {
+    Ok(match path {
+        Some(path) if !input::is_stdio(path) => Box::new(
+            File::create(path).with_context(|| format!("failed to create {}", path.display()))?,
+        ),
+        _ => Box::new(std::io::stdout().lock()),
+    })
+}
@@ -0,0 +1,134 @@
+//! Parquet output, for DuckDB, pandas, and other columnar tools.
+//!
+//! Rows are buffered column by column and flushed as a row group every
+//! [`ROW_GROUP_SIZE`] rows, so memory stays bounded however large the corpus.
+
+use anyhow::{Context, Result};
+use parquet::basic::Compression;
+use parquet::data_type::{ByteArray, ByteArrayType, DataType, Int32Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::{SerializedFileWriter, SerializedRowGroupWriter};
+use parquet::schema::parser::parse_message_type;
+use std::fs::File;
+use std::sync::Arc;
+
+use super::{Field, Sink, Source, Value};
+
+const ROW_GROUP_SIZE: usize = 100_000;
+
+/// The message type for the selected fields, in order.
+fn schema(fields: &[Field]) -> String {
+    let mut schema = String::from("message entry {\n");
+    for field in fields {
+        let column = if field.is_integer() {
+            format!("    REQUIRED INT32 {};\n", field.name())
+        } else {
+            format!("    REQUIRED BYTE_ARRAY {} (UTF8);\n", field.name())
+        };
+        schema.push_str(&column);
+    }
+    schema.push('}');
+    schema
+}
+
+pub(super) struct ParquetSink {
+    writer: SerializedFileWriter<File>,
+    fields: Vec<Field>,
+    /// The row group being built, one buffer per field.
+    columns: Vec<Column>,
+    rows: usize,
+}
+
+enum Column {
+    Text(Vec<ByteArray>),
+    Integer(Vec<i32>),
+}
+
+impl ParquetSink {
+    pub(super) fn new(file: File, fields: Vec<Field>) -> Result<Self> {
+        let schema = parse_message_type(&schema(&fields))?;
+        let props = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .set_max_row_group_size(ROW_GROUP_SIZE)
+            .build();
+        let mut sink = ParquetSink {
+            writer: SerializedFileWriter::new(file, Arc::new(schema), Arc::new(props))?,
+            fields,
+            columns: Vec::new(),
+            rows: 0,
+        };
+        sink.reset();
+        Ok(sink)
+    }
+
+    fn reset(&mut self) {
+        self.columns = self
+            .fields
+            .iter()
+            .map(|f| {
+                if f.is_integer() {
+                    Column::Integer(Vec::new())
+                } else {
+                    Column::Text(Vec::new())
+                }
+            })
+            .collect();
+        self.rows = 0;
+    }
+
+    /// Write the buffered rows as one row group and start a new one.
+    fn flush(&mut self) -> Result<()> {
+        if self.rows == 0 {
+            return Ok(());
+        }
+        let mut group = self.writer.next_row_group()?;
+        for column in &self.columns {
+            match column {
+                Column::Text(values) => write_column::<ByteArrayType>(&mut group, values)?,
+                Column::Integer(values) => write_column::<Int32Type>(&mut group, values)?,
+            }
+        }
+        group.close()?;
+        self.reset();
+        Ok(())
+    }
+}
+
+/// Write the next column of `group`, which must match `T` in the schema.
+fn write_column<T: DataType>(
+    group: &mut SerializedRowGroupWriter<'_, File>,
+    values: &[T::T],
+) -> Result<()> {
+    let mut column = group
+        .next_column()?
+        .context("more columns written than the schema declares")?;
+    column.typed::<T>().write_batch(values, None, None)?;
+    column.close()?;
+    Ok(())
+}
+
+impl Sink for ParquetSink {
+    fn add(&mut self, source: &Source<'_>) -> Result<usize> {
+        for entry in source.entries {
+            for (column, &field) in self.columns.iter_mut().zip(&self.fields) {
+                match (column, source.value(entry, field)) {
+                    (Column::Text(values), Value::Text(text)) => values.push(text.into()),
+                    // Every integer field is a count or size that fits in i32.
+                    (Column::Integer(values), Value::Integer(n)) => values.push(n as i32),
+                    _ => unreachable!("column type follows Field::is_integer"),
+                }
+            }
+        }
+        self.rows += source.entries.len();
+        if self.rows >= ROW_GROUP_SIZE {
+            self.flush()?;
+        }
+        Ok(source.entries.len())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.flush()?;
+        self.writer.close()?;
+        Ok(())
+    }
+}
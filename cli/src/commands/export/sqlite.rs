@@ -0,0 +1,301 @@
+//! SQLite output: normalized tables for joins and counts over a large corpus.
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OpenFlags, params};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use super::{Sink, Source, direction_name};
+use crate::commands::sha256_hex;
+
+/// Tables are created up front; indices are built in [`SqliteSink::finish`],
+/// after the bulk insert, which is much faster than maintaining them per row.
+const SCHEMA: &str = "
+CREATE TABLE puzzles (
+    id        INTEGER PRIMARY KEY,
+    file      TEXT NOT NULL,
+    sha256    TEXT NOT NULL,
+    title     TEXT NOT NULL,
+    author    TEXT NOT NULL,
+    copyright TEXT NOT NULL,
+    notes     TEXT NOT NULL,
+    version   TEXT NOT NULL,
+    width     INTEGER NOT NULL,
+    height    INTEGER NOT NULL
+);
+CREATE TABLE entries (
+    puzzle_id INTEGER NOT NULL REFERENCES puzzles(id),
+    direction TEXT NOT NULL,
+    number    INTEGER NOT NULL,
+    clue      TEXT NOT NULL,
+    answer    TEXT NOT NULL,
+    row       INTEGER NOT NULL,
+    col       INTEGER NOT NULL,
+    length    INTEGER NOT NULL
+);
+CREATE TABLE rebus (
+    puzzle_id INTEGER NOT NULL REFERENCES puzzles(id),
+    row       INTEGER NOT NULL,
+    col       INTEGER NOT NULL,
+    text      TEXT NOT NULL
+);
+CREATE TABLE circles (
+    puzzle_id INTEGER NOT NULL REFERENCES puzzles(id),
+    row       INTEGER NOT NULL,
+    col       INTEGER NOT NULL
+);
+CREATE TABLE meta (
+    key   TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE sources (
+    path  TEXT PRIMARY KEY,
+    size  INTEGER NOT NULL,
+    mtime INTEGER NOT NULL
+);
+";
+
+const INDICES: &str = "
+CREATE INDEX puzzles_sha256 ON puzzles(sha256);
+CREATE INDEX entries_puzzle ON entries(puzzle_id);
+CREATE INDEX entries_answer ON entries(answer);
+CREATE INDEX entries_clue ON entries(clue);
+CREATE INDEX entries_length ON entries(length);
+CREATE INDEX rebus_puzzle ON rebus(puzzle_id);
+CREATE INDEX circles_puzzle ON circles(puzzle_id);
+";
+
+/// The `created_by` value in `meta`, which marks a database as one this tool
+/// wrote and may replace.
+const CREATED_BY: &str = concat!("puz ", env!("CARGO_PKG_VERSION"));
+
+/// Whether [`SqliteSink::create`] may replace what is at `path`: nothing, or
+/// a SQLite database with the `meta` row puz writes. Anything else could be
+/// unrelated data behind a mistyped path.
+pub(super) fn replaceable(path: &Path) -> bool {
+    if !path.exists() {
+        return true;
+    }
+    let Ok(conn) = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY) else {
+        return false;
+    };
+    conn.query_row(
+        "SELECT value FROM meta WHERE key = 'created_by'",
+        [],
+        |row| row.get::<_, String>(0),
+    )
+    .is_ok_and(|value| value.starts_with("puz "))
+}
+
+pub(super) struct SqliteSink {
+    conn: Connection,
+    /// The source directory and its files, taken before any file is read and
+    /// recorded in `finish`, so an interrupted build is never current. `None`
+    /// for a filtered export, which must never pass for a full index.
+    sources: Option<(String, Vec<SourceFile>)>,
+}
+
+impl SqliteSink {
+    /// Create a fresh database at `path`, replacing any file already there.
+    /// Callers check [`replaceable`] first.
+    ///
+    /// `sources` is the directory and its files when the database will hold
+    /// every entry in them; they are recorded so [`is_current`] can accept the
+    /// database as an index of that directory.
+    pub(super) fn create(path: &Path, sources: Option<(&Path, &[PathBuf])>) -> Result<Self> {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        let conn = Connection::open(path)?;
+        // The database is rebuilt from scratch on every export, so durability
+        // mid-run buys nothing; a crash just means running the export again.
+        conn.execute_batch(
+            "PRAGMA journal_mode = OFF;
+             PRAGMA synchronous = OFF;",
+        )?;
+        conn.execute_batch(SCHEMA)?;
+        // Marked before any data goes in, so even a database left by an
+        // interrupted run can be replaced without --force.
+        conn.execute(
+            "INSERT INTO meta (key, value) VALUES ('created_by', ?1)",
+            [CREATED_BY],
+        )?;
+        // One transaction for the whole export, committed in `finish`.
+        conn.execute_batch("BEGIN")?;
+        let sources = match sources {
+            Some((dir, files)) => Some((source_dir(dir)?, fingerprint(dir, files)?)),
+            None => None,
+        };
+        Ok(SqliteSink { conn, sources })
+    }
+}
+
+/// A source file as the database records it, to tell when the directory has
+/// changed since the database was built.
+#[derive(PartialEq)]
+struct SourceFile {
+    /// The path below the source directory.
+    path: String,
+    size: i64,
+    /// Modification time in nanoseconds since the Unix epoch.
+    mtime: i64,
+}
+
+fn source_dir(dir: &Path) -> Result<String> {
+    let dir = std::fs::canonicalize(dir)
+        .with_context(|| format!("failed to resolve {}", dir.display()))?;
+    Ok(dir.to_string_lossy().into_owned())
+}
+
+fn fingerprint(dir: &Path, files: &[PathBuf]) -> Result<Vec<SourceFile>> {
+    files
+        .iter()
+        .map(|file| {
+            let meta = std::fs::metadata(file)
+                .with_context(|| format!("failed to read {}", file.display()))?;
+            let mtime = meta
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos() as i64);
+            Ok(SourceFile {
+                path: file
+                    .strip_prefix(dir)
+                    .unwrap_or(file)
+                    .to_string_lossy()
+                    .into_owned(),
+                size: meta.len() as i64,
+                mtime,
+            })
+        })
+        .collect()
+}
+
+/// Whether the database at `db` was built from `dir` and none of `files`
+/// (the current walk of `dir`) has been added, removed, or modified since.
+///
+/// A database without the source tables, such as one from an older version,
+/// is never current.
+pub(super) fn is_current(db: &Path, dir: &Path, files: &[PathBuf]) -> Result<bool> {
+    let conn = Connection::open(db)?;
+    let has_sources: bool = conn.query_row(
+        "SELECT count(*) = 2 FROM sqlite_master WHERE type = 'table' AND name IN ('meta', 'sources')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_sources {
+        return Ok(false);
+    }
+    let recorded_dir: Option<String> = conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'source_dir'",
+            [],
+            |row| row.get(0),
+        )
+        .ok();
+    if recorded_dir != Some(source_dir(dir)?) {
+        return Ok(false);
+    }
+    let recorded = conn
+        .prepare("SELECT path, size, mtime FROM sources ORDER BY rowid")?
+        .query_map([], |row| {
+            Ok(SourceFile {
+                path: row.get(0)?,
+                size: row.get(1)?,
+                mtime: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(recorded == fingerprint(dir, files)?)
+}
+
+impl Sink for SqliteSink {
+    fn add(&mut self, source: &Source<'_>) -> Result<usize> {
+        let puzzle = source.puzzle;
+        let info = &puzzle.info;
+        let hash = sha256_hex(source.data);
+
+        self.conn
+            .prepare_cached(
+                "INSERT INTO puzzles
+                 (file, sha256, title, author, copyright, notes, version, width, height)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?
+            .execute(params![
+                source.file,
+                hash,
+                info.title,
+                info.author,
+                info.copyright,
+                info.notes,
+                info.version,
+                info.width,
+                info.height,
+            ])?;
+        let id = self.conn.last_insert_rowid();
+
+        let mut insert = self.conn.prepare_cached(
+            "INSERT INTO entries
+             (puzzle_id, direction, number, clue, answer, row, col, length)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        for entry in source.entries {
+            insert.execute(params![
+                id,
+                direction_name(entry.direction),
+                entry.number,
+                entry.clue,
+                entry.answer,
+                entry.row,
+                entry.col,
+                entry.answer.chars().count(),
+            ])?;
+        }
+
+        if let Some(rebus) = &puzzle.extensions.rebus {
+            let mut insert = self.conn.prepare_cached(
+                "INSERT INTO rebus (puzzle_id, row, col, text) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for (r, row) in rebus.grid.iter().enumerate() {
+                for c in 0..row.len() {
+                    if let Some(text) = rebus.get(r, c) {
+                        insert.execute(params![id, r, c, text])?;
+                    }
+                }
+            }
+        }
+
+        if let Some(circles) = &puzzle.extensions.circles {
+            let mut insert = self
+                .conn
+                .prepare_cached("INSERT INTO circles (puzzle_id, row, col) VALUES (?1, ?2, ?3)")?;
+            for (r, row) in circles.iter().enumerate() {
+                for (c, &circled) in row.iter().enumerate() {
+                    if circled {
+                        insert.execute(params![id, r, c])?;
+                    }
+                }
+            }
+        }
+
+        Ok(source.entries.len())
+    }
+
+    fn finish(self: Box<Self>) -> Result<()> {
+        if let Some((dir, files)) = &self.sources {
+            self.conn.execute(
+                "INSERT INTO meta (key, value) VALUES ('source_dir', ?1)",
+                [dir],
+            )?;
+            let mut insert = self
+                .conn
+                .prepare("INSERT INTO sources (path, size, mtime) VALUES (?1, ?2, ?3)")?;
+            for source in files {
+                insert.execute(params![source.path, source.size, source.mtime])?;
+            }
+        }
+        self.conn.execute_batch("COMMIT")?;
+        self.conn.execute_batch(INDICES)?;
+        Ok(())
+    }
+}
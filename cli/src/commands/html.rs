@@ -0,0 +1,137 @@
+//! Export a puzzle as a self-contained interactive HTML solver.
+//!
+//! The page is a single file: the markup, styles, and script come from an
+//! embedded template, and the puzzle is inlined as JSON. Nothing is fetched
+//! at solve time, so the file can be opened locally or posted anywhere that
+//! serves static pages.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use puz_parse::{Direction, Puzzle};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::commands::{cell_solution, circled, given};
+use crate::input;
+
+/// The solver page. `{{PUZZLE_JSON}}` is replaced with the puzzle data.
+const TEMPLATE: &str = include_str!("solver.html");
+
+#[derive(Args)]
+pub(crate) struct HtmlArgs {
+    /// the .puz file to export (- for stdin)
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+
+    /// where to write the HTML page (- for stdout)
+    #[arg(short, long, value_name = "FILE")]
+    output: PathBuf,
+}
+
+/// Everything the solver script needs, in the shape it reads it.
+#[derive(Serialize)]
+struct PageData<'a> {
+    title: &'a str,
+    author: &'a str,
+    copyright: &'a str,
+    notes: &'a str,
+    width: u8,
+    height: u8,
+    /// Row-major cells; `None` is a black square.
+    cells: Vec<Vec<Option<Cell>>>,
+    across: Vec<Entry<'a>>,
+    down: Vec<Entry<'a>>,
+}
+
+#[derive(Serialize)]
+struct Cell {
+    /// The full solution, including multi-letter rebus text.
+    solution: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    number: Option<u16>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    circled: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    given: bool,
+}
+
+#[derive(Serialize)]
+struct Entry<'a> {
+    number: u16,
+    clue: &'a str,
+    row: usize,
+    col: usize,
+    length: usize,
+}
+
+pub(crate) fn run(args: HtmlArgs) -> Result<()> {
+    let data = input::read(&args.file)?;
+    let parsed = Puzzle::reader()
+        .from_bytes_verbose(&data)
+        .with_context(|| format!("failed to parse {}", args.file.display()))?;
+    for warning in &parsed.warnings {
+        eprintln!("Warning in {}: {warning}", args.file.display());
+    }
+
+    let html = render_page(&parsed.result)?;
+    input::write(&args.output, html)?;
+    Ok(())
+}
+
+fn render_page(puzzle: &Puzzle) -> Result<String> {
+    let entries = puzzle.clue_answers();
+    let numbers: HashMap<(usize, usize), u16> =
+        entries.iter().map(|e| ((e.row, e.col), e.number)).collect();
+    let cells = puzzle
+        .grid
+        .blank
+        .iter()
+        .enumerate()
+        .map(|(r, row)| {
+            row.chars()
+                .enumerate()
+                .map(|(c, ch)| {
+                    (ch != '.').then(|| Cell {
+                        solution: cell_solution(puzzle, r, c),
+                        number: numbers.get(&(r, c)).copied(),
+                        circled: circled(puzzle, r, c),
+                        given: given(puzzle, r, c),
+                    })
+                })
+                .collect()
+        })
+        .collect();
+
+    let entries_for = |direction: Direction| {
+        entries
+            .iter()
+            .filter(|e| e.direction == direction)
+            .map(|e| Entry {
+                number: e.number,
+                clue: &e.clue,
+                row: e.row,
+                col: e.col,
+                length: e.answer.chars().count(),
+            })
+            .collect()
+    };
+
+    let page = PageData {
+        title: &puzzle.info.title,
+        author: &puzzle.info.author,
+        copyright: &puzzle.info.copyright,
+        notes: &puzzle.info.notes,
+        width: puzzle.info.width,
+        height: puzzle.info.height,
+        cells,
+        across: entries_for(Direction::Across),
+        down: entries_for(Direction::Down),
+    };
+
+    // `<` only appears inside JSON strings, where the `\u003c` escape means
+    // the same thing; escaping it keeps puzzle text from closing the
+    // surrounding <script> element.
+    let json = serde_json::to_string(&page)?.replace('<', "\\u003c");
+    Ok(TEMPLATE.replace("{{PUZZLE_JSON}}", &json))
+}
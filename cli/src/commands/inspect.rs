@@ -1,35 +1,84 @@
 //! Inspect a single `.puz` file's extension sections.
+//!
+//! Like `dump`, these commands read the sections with
+//! [`raw::scan_sections`] instead of parsing the puzzle, so a file whose
+//! rebus or flag data is broken can still be looked at section by section.
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use clap::Subcommand;
 use puz_parse::raw;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
+use crate::commands::to_stdout;
+use crate::input;
 use crate::render;
 
 #[derive(Subcommand)]
 pub(crate) enum InspectKind {
     /// list extension sections (GRBS, RTBL, GEXT, ...)
     Sections {
-        /// the .puz file to read
+        /// the .puz file to read (- for stdin)
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// map each GEXT flag bit (circled, given, marked incorrect) over the grid
+    Gext {
+        /// the .puz file to read (- for stdin)
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// show the GRBS grid with its RTBL keys and values
+    Rebus {
+        /// the .puz file to read (- for stdin)
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// decode the LTIM timer
+    Timer {
+        /// the .puz file to read (- for stdin)
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// hex dump one section's payload
+    Section {
+        /// the section tag, e.g. GRBS
+        #[arg(value_name = "TAG")]
+        tag: String,
+        /// the .puz file to read (- for stdin)
         #[arg(value_name = "FILE")]
         file: PathBuf,
     },
 }
 
+/// The GEXT flag bits, from PUZ.md, and what each means.
+const GEXT_FLAGS: [(u8, &str); 5] = [
+    (0x80, "circled"),
+    (0x40, "given"),
+    (0x20, "marked incorrect"),
+    (0x10, "previously marked incorrect"),
+    (0x0F, "unknown bits"),
+];
+
 pub(crate) fn run(what: InspectKind) -> Result<()> {
-    match what {
-        InspectKind::Sections { file } => inspect_sections(&file),
-    }
+    to_stdout(|out| match what {
+        InspectKind::Sections { file } => inspect_sections(out, &file),
+        InspectKind::Gext { file } => inspect_gext(out, &file),
+        InspectKind::Rebus { file } => inspect_rebus(out, &file),
+        InspectKind::Timer { file } => inspect_timer(out, &file),
+        InspectKind::Section { tag, file } => inspect_section(out, &file, &tag),
+    })
 }
 
-fn inspect_sections(path: &PathBuf) -> Result<()> {
-    let data = std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+fn inspect_sections(out: &mut dyn Write, path: &Path) -> Result<()> {
+    let data = input::read(path)?;
 
     let header = raw::read_header(&data);
-    println!("{}", render::bold(path.display()));
+    writeln!(out, "{}", render::bold(path.display()))?;
     if let Some(h) = &header {
-        println!(
+        writeln!(
+            out,
             "{}",
             render::dim(format!(
                 "grid {}x{} = {} cells",
@@ -37,12 +86,12 @@ fn inspect_sections(path: &PathBuf) -> Result<()> {
                 h.height,
                 h.width as usize * h.height as usize
             ))
-        );
+        )?;
     }
 
     let sections = raw::scan_sections(&data);
     if sections.is_empty() {
-        println!("{}", render::dim("no extension sections found"));
+        writeln!(out, "{}", render::dim("no extension sections found"))?;
         return Ok(());
     }
 
@@ -58,14 +107,318 @@ fn inspect_sections(path: &PathBuf) -> Result<()> {
             s.tag.clone(),
             format!("0x{:X}", s.offset),
             s.length.to_string(),
-            format!("0x{:04X}", s.checksum),
+            checksum_status(s),
             summarize(s, board),
         ]);
     }
-    println!("{table}");
+    writeln!(out, "{table}")?;
+    Ok(())
+}
+
+/// Find the section tagged `tag`, or explain that the file has none.
+fn require<'a>(
+    sections: &'a [raw::RawSection],
+    tag: &str,
+    path: &Path,
+) -> Result<&'a raw::RawSection> {
+    sections
+        .iter()
+        .find(|s| s.tag == tag)
+        .ok_or_else(|| anyhow::anyhow!("{} has no {tag} section", path.display()))
+}
+
+/// Print the file name and the section's declared size, warning when a grid
+/// section doesn't cover the board.
+fn print_section_heading(
+    out: &mut dyn Write,
+    path: &Path,
+    section: &raw::RawSection,
+    board: Option<usize>,
+) -> Result<()> {
+    writeln!(out, "{}", render::bold(path.display()))?;
+    writeln!(
+        out,
+        "{}",
+        render::dim(format!(
+            "{} at 0x{:X}, {} bytes",
+            section.tag,
+            section.offset,
+            section.data.len()
+        ))
+    )?;
+    if let Some(board) = board
+        && section.data.len() != board
+    {
+        writeln!(
+            out,
+            "{}",
+            render::yellow(format!(
+                "{} is {} bytes but the board is {board} cells",
+                section.tag,
+                section.data.len()
+            ))
+        )?;
+    }
+    Ok(())
+}
+
+/// What a grid section is laid over: the header's dimensions and the flat
+/// solution grid, which is empty when the file is too short to hold it.
+struct Board {
+    width: usize,
+    cells: usize,
+    solution: Vec<u8>,
+}
+
+impl Board {
+    fn read(data: &[u8], path: &Path) -> Result<Self> {
+        let header = raw::read_header(data)
+            .ok_or_else(|| anyhow::anyhow!("{} is too short for a header", path.display()))?;
+        let solution = raw::read_grids(data)
+            .map(|g| g.solution.concat())
+            .unwrap_or_default();
+        Ok(Board {
+            width: header.width as usize,
+            cells: header.width as usize * header.height as usize,
+            solution,
+        })
+    }
+
+    fn is_black(&self, row: usize, col: usize) -> bool {
+        self.solution.get(row * self.width + col) == Some(&b'.')
+    }
+}
+
+fn inspect_gext(out: &mut dyn Write, path: &Path) -> Result<()> {
+    let data = input::read(path)?;
+    let board = Board::read(&data, path)?;
+    let sections = raw::scan_sections(&data);
+    let gext = require(&sections, "GEXT", path)?;
+    print_section_heading(out, path, gext, Some(board.cells))?;
+    writeln!(
+        out,
+        "{}",
+        render::dim("X = flag set, - = clear, # = black square")
+    )?;
+
+    for (bit, name) in GEXT_FLAGS {
+        let count = gext.data.iter().filter(|&&b| b & bit != 0).count();
+        writeln!(out)?;
+        writeln!(out, "0x{bit:02X} {name}: {count} square(s)")?;
+        if count == 0 || board.width == 0 {
+            continue;
+        }
+        for (i, row) in gext.data.chunks(board.width).enumerate() {
+            let rendered: String = row
+                .iter()
+                .enumerate()
+                .map(|(j, &b)| match b & bit {
+                    0 if board.is_black(i, j) => '#',
+                    0 => '-',
+                    _ => 'X',
+                })
+                .collect();
+            writeln!(out, "  {i:>2} {rendered}")?;
+        }
+    }
+    Ok(())
+}
+
+/// One `key:value` entry of an RTBL string, or the text that didn't parse.
+enum RtblEntry {
+    Valid(u8, String),
+    Malformed(String),
+}
+
+/// Split an RTBL string like `" 0:HEART; 1:CLUB;"` into its entries.
+fn parse_rtbl(data: &[u8]) -> Vec<RtblEntry> {
+    let text = String::from_utf8_lossy(data);
+    text.trim_end_matches('\0')
+        .split(';')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.split_once(':') {
+            Some((key, value)) => match key.trim().parse() {
+                Ok(key) => RtblEntry::Valid(key, value.trim().to_string()),
+                Err(_) => RtblEntry::Malformed(entry.to_string()),
+            },
+            None => RtblEntry::Malformed(entry.to_string()),
+        })
+        .collect()
+}
+
+fn inspect_rebus(out: &mut dyn Write, path: &Path) -> Result<()> {
+    let data = input::read(path)?;
+    let board = Board::read(&data, path)?;
+    let sections = raw::scan_sections(&data);
+    let grbs = sections.iter().find(|s| s.tag == "GRBS");
+    let rtbl = sections.iter().find(|s| s.tag == "RTBL");
+    if grbs.is_none() && rtbl.is_none() {
+        anyhow::bail!("{} has no GRBS or RTBL section", path.display());
+    }
+
+    let entries = rtbl.map(|s| parse_rtbl(&s.data)).unwrap_or_default();
+    let mut table: BTreeMap<u8, String> = BTreeMap::new();
+    for entry in &entries {
+        if let RtblEntry::Valid(key, value) = entry {
+            table.insert(*key, value.clone());
+        }
+    }
+
+    // Every square using each key. GRBS values are one more than the RTBL
+    // key they refer to, so 0 can mean "no rebus".
+    let mut squares: BTreeMap<u8, Vec<String>> = BTreeMap::new();
+    if let Some(grbs) = grbs {
+        print_section_heading(out, path, grbs, Some(board.cells))?;
+        writeln!(
+            out,
+            "{}",
+            render::dim("each square shows its RTBL key; . = no rebus, # = black square")
+        )?;
+        for (i, row) in grbs.data.chunks(board.width.max(1)).enumerate() {
+            let mut rendered = String::new();
+            for (j, &b) in row.iter().enumerate() {
+                let cell = match b {
+                    0 if board.is_black(i, j) => "#".to_string(),
+                    0 => ".".to_string(),
+                    _ => {
+                        squares
+                            .entry(b - 1)
+                            .or_default()
+                            .push(format!("r{}c{}", i + 1, j + 1));
+                        (b - 1).to_string()
+                    }
+                };
+                rendered.push_str(&format!("{cell:>3}"));
+            }
+            writeln!(out, "  {i:>2}{rendered}")?;
+        }
+    } else {
+        writeln!(out, "{}", render::bold(path.display()))?;
+        writeln!(out, "{}", render::yellow("no GRBS section; RTBL is unused"))?;
+    }
+    if rtbl.is_none() {
+        writeln!(
+            out,
+            "{}",
+            render::yellow("no RTBL section; rebus squares have no text")
+        )?;
+    }
+
+    let keys: BTreeSet<u8> = table.keys().chain(squares.keys()).copied().collect();
+    if !keys.is_empty() {
+        writeln!(out)?;
+        let mut key_table = render::bordered_table();
+        key_table.set_header(vec!["key", "GRBS", "value", "squares"]);
+        for key in keys {
+            let value = match table.get(&key) {
+                Some(value) => value.clone(),
+                None => render::red("(missing from RTBL)"),
+            };
+            let used = match squares.get(&key) {
+                Some(cells) => cells.join(" "),
+                None => render::yellow("(unused)"),
+            };
+            key_table.add_row(vec![
+                key.to_string(),
+                (key as u16 + 1).to_string(),
+                value,
+                used,
+            ]);
+        }
+        writeln!(out, "{key_table}")?;
+    }
+    for entry in &entries {
+        if let RtblEntry::Malformed(text) = entry {
+            writeln!(
+                out,
+                "{}",
+                render::yellow(format!("malformed RTBL entry {text:?}"))
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn inspect_timer(out: &mut dyn Write, path: &Path) -> Result<()> {
+    let data = input::read(path)?;
+    let sections = raw::scan_sections(&data);
+    let ltim = require(&sections, "LTIM", path)?;
+    print_section_heading(out, path, ltim, None)?;
+
+    let text = String::from_utf8_lossy(&ltim.data);
+    let text = text.trim_end_matches('\0');
+    writeln!(out, "raw: {text:?}")?;
+    let Some((elapsed, state)) = text.split_once(',') else {
+        writeln!(out, "{}", render::yellow("expected \"<seconds>,<state>\""))?;
+        return Ok(());
+    };
+    match elapsed.trim().parse::<u64>() {
+        Ok(secs) => writeln!(
+            out,
+            "elapsed: {secs}s ({}:{:02}:{:02})",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )?,
+        Err(_) => writeln!(
+            out,
+            "{}",
+            render::yellow(format!("elapsed time {elapsed:?} is not a number"))
+        )?,
+    }
+    match state.trim() {
+        "0" => writeln!(out, "state: 0 (running when saved)")?,
+        "1" => writeln!(out, "state: 1 (stopped)")?,
+        other => writeln!(
+            out,
+            "{}",
+            render::yellow(format!("state {other:?} is not 0 or 1"))
+        )?,
+    }
+    Ok(())
+}
+
+fn inspect_section(out: &mut dyn Write, path: &Path, tag: &str) -> Result<()> {
+    let data = input::read(path)?;
+    let sections = raw::scan_sections(&data);
+    let section = require(&sections, &tag.to_ascii_uppercase(), path)?;
+    print_section_heading(out, path, section, None)?;
+    writeln!(
+        out,
+        "{}",
+        render::dim(format!(
+            "declared length {}, checksum 0x{:04X}",
+            section.length, section.checksum
+        ))
+    )?;
+    // Offsets are into the file, so they line up with `puz dump hex`.
+    let start = section.offset + 8;
+    for (i, chunk) in section.data.chunks(render::HEX_WIDTH).enumerate() {
+        writeln!(
+            out,
+            "{}",
+            render::hex_line(start + i * render::HEX_WIDTH, chunk).trim_end()
+        )?;
+    }
     Ok(())
 }
 
+/// The stored checksum, then OK if it matches the data or BAD with the
+/// checksum the data actually has.
+fn checksum_status(section: &raw::RawSection) -> String {
+    if section.checksum_ok() {
+        format!("0x{:04X} {}", section.checksum, render::green("OK"))
+    } else {
+        format!(
+            "0x{:04X} {} (data 0x{:04X})",
+            section.checksum,
+            render::red("BAD"),
+            section.computed_checksum()
+        )
+    }
+}
+
 /// A short human summary of a section's contents.
 fn summarize(section: &raw::RawSection, board: usize) -> String {
     match section.tag.as_str() {
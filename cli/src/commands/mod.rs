@@ -4,41 +4,142 @@
 //! clap arguments and a `run` entry point. `main` dispatches to these; shared
 //! presentation lives in [`crate::render`].
 
+pub(crate) mod build;
+pub(crate) mod convert;
+pub(crate) mod dedupe;
+pub(crate) mod diff;
 pub(crate) mod dump;
+pub(crate) mod edit;
 pub(crate) mod export;
+pub(crate) mod html;
 pub(crate) mod inspect;
 pub(crate) mod parse_json;
+pub(crate) mod play;
+pub(crate) mod print;
+pub(crate) mod search;
+pub(crate) mod show;
+pub(crate) mod stats;
+pub(crate) mod text;
 pub(crate) mod validate;
 
-use std::path::{Path, PathBuf};
+use puz_parse::{ClueAnswer, Direction, Puzzle};
+use sha2::{Digest, Sha256};
+use std::io::Write;
 
-/// Recursively collect every `.puz` file under `dir`, in no particular order.
-///
-/// Shared by the directory-walking commands (`validate`, `export`).
-pub(crate) fn collect_puz_files(dir: &Path) -> Vec<PathBuf> {
-    let mut out = Vec::new();
-    collect_into(dir, &mut out);
-    out
+/// Items handed to the thread pool at a time by [`parallel_in_order`]. Large
+/// enough to keep every thread busy, small enough that a chunk's results
+/// (parsed puzzles, file bytes) stay cheap to hold.
+const CHUNK_PER_JOB: usize = 64;
+
+/// The default for `--jobs`: one thread per available CPU.
+pub(crate) fn default_jobs() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
 }
 
-fn collect_into(dir: &Path, out: &mut Vec<PathBuf>) {
-    let Ok(entries) = std::fs::read_dir(dir) else {
-        return;
-    };
-    for entry in entries.flatten() {
-        // Use the directory entry's file type, which comes from `readdir` and
-        // avoids an extra `stat` syscall per entry (the old `path.is_dir()`
-        // stat'd all 48k+ files). Fall back to a stat only if the type is
-        // unknown (rare, e.g. some network filesystems).
-        let is_dir = match entry.file_type() {
-            Ok(ft) => ft.is_dir(),
-            Err(_) => entry.path().is_dir(),
-        };
-        let path = entry.path();
-        if is_dir {
-            collect_into(&path, out);
-        } else if path.extension().and_then(|e| e.to_str()) == Some("puz") {
-            out.push(path);
+/// Run `work` on every item across `jobs` threads, and hand each result to
+/// `each` in the order of `items`.
+///
+/// Items are processed a chunk at a time, so output stays in the same order
+/// as a sequential run while memory stays bounded on large corpora. An error
+/// from `each` stops the run.
+pub(crate) fn parallel_in_order<I: Sync, T: Send>(
+    items: impl IntoIterator<Item = I>,
+    jobs: usize,
+    work: impl Fn(&I) -> T + Sync,
+    mut each: impl FnMut(&I, T) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    use rayon::prelude::*;
+
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(jobs).build()?;
+    let mut items = items.into_iter();
+    loop {
+        let chunk: Vec<I> = items.by_ref().take(jobs * CHUNK_PER_JOB).collect();
+        if chunk.is_empty() {
+            return Ok(());
+        }
+        let results: Vec<T> = pool.install(|| chunk.par_iter().map(&work).collect());
+        for (item, result) in chunk.iter().zip(results) {
+            each(item, result)?;
         }
     }
 }
+
+/// Write a command's output through a locked stdout.
+///
+/// A closed downstream pipe (e.g. `puz show x.puz | head`) is a normal way to
+/// stop reading, so it ends the command cleanly instead of as an error.
+pub(crate) fn to_stdout(
+    write: impl FnOnce(&mut dyn Write) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut out = std::io::stdout().lock();
+    match write(&mut out).and_then(|()| Ok(out.flush()?)) {
+        Err(e) if is_broken_pipe(&e) => Ok(()),
+        result => result,
+    }
+}
+
+pub(crate) fn is_broken_pipe(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<std::io::Error>()
+        .is_some_and(|e| e.kind() == std::io::ErrorKind::BrokenPipe)
+}
+
+/// The full solution at one cell: the rebus text if the cell has one, else the
+/// solution character.
+///
+/// Shared by the commands that render a solved grid (`print`, `html`, `show`,
+/// `text`).
+pub(crate) fn cell_solution(puzzle: &Puzzle, row: usize, col: usize) -> String {
+    if let Some(text) = puzzle
+        .extensions
+        .rebus
+        .as_ref()
+        .and_then(|r| r.get(row, col))
+    {
+        return text.to_string();
+    }
+    puzzle
+        .grid
+        .solution
+        .get(row)
+        .and_then(|r| r.chars().nth(col))
+        .map(String::from)
+        .unwrap_or_default()
+}
+
+/// The solution at each square of an entry, in order: [`cell_solution`] for
+/// every square the answer covers, so rebus squares come back whole.
+pub(crate) fn answer_cells(puzzle: &Puzzle, entry: &ClueAnswer) -> Vec<String> {
+    let (dr, dc) = match entry.direction {
+        Direction::Across => (0, 1),
+        Direction::Down => (1, 0),
+    };
+    (0..entry.answer.chars().count())
+        .map(|i| cell_solution(puzzle, entry.row + i * dr, entry.col + i * dc))
+        .collect()
+}
+
+/// Whether the square at `row`, `col` is circled (GEXT `0x80`).
+pub(crate) fn circled(puzzle: &Puzzle, row: usize, col: usize) -> bool {
+    flag(puzzle.extensions.circles.as_ref(), row, col)
+}
+
+/// Whether the square at `row`, `col` is given to the solver (GEXT `0x40`).
+pub(crate) fn given(puzzle: &Puzzle, row: usize, col: usize) -> bool {
+    flag(puzzle.extensions.given.as_ref(), row, col)
+}
+
+fn flag(grid: Option<&Vec<Vec<bool>>>, row: usize, col: usize) -> bool {
+    grid.and_then(|g| g.get(row))
+        .and_then(|r| r.get(col))
+        .copied()
+        .unwrap_or(false)
+}
+
+/// The SHA-256 of `data` as lowercase hex, for content hashes stored by
+/// `export --format sqlite` and the `validate` cache.
+pub(crate) fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
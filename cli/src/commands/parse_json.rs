@@ -3,12 +3,14 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use puz_parse::Puzzle;
-use std::io::{self, Write};
 use std::path::Path;
 
+use crate::commands::to_stdout;
+use crate::input;
+
 #[derive(Args)]
 pub(crate) struct ParseArgs {
-    /// puzzle files to parse
+    /// puzzle files to parse (- for stdin)
     #[arg(value_name = "PUZZLE", required = true, num_args = 1..)]
     pub(crate) files: Vec<String>,
 
@@ -76,15 +78,12 @@ pub(crate) fn run(args: ParseArgs) -> Result<()> {
     };
 
     match args.output.as_deref() {
-        Some(path) => {
+        Some(path) if !input::is_stdio(Path::new(path)) => {
             std::fs::write(path, json_output)
                 .with_context(|| format!("Failed to write to {path}"))?;
         }
-        None => {
-            io::stdout()
-                .write_all(json_output.as_bytes())
-                .context("Failed to write to stdout")?;
-            println!();
+        _ => {
+            to_stdout(|out| Ok(writeln!(out, "{json_output}")?))?;
         }
     }
 
@@ -93,15 +92,20 @@ pub(crate) fn run(args: ParseArgs) -> Result<()> {
 
 fn process_file(path: &str) -> Result<Puzzle> {
     let file_path = Path::new(path);
-    if !file_path.exists() {
-        anyhow::bail!("File does not exist: {}", path);
-    }
-    if !file_path.is_file() {
-        anyhow::bail!("Path is not a file: {}", path);
+    // Stdin (`-`) and archive members (`pack.zip!/a.puz`) aren't paths on
+    // disk.
+    if input::is_disk_path(file_path) {
+        if !file_path.exists() {
+            anyhow::bail!("File does not exist: {}", path);
+        }
+        if !file_path.is_file() {
+            anyhow::bail!("Path is not a file: {}", path);
+        }
     }
 
+    let data = input::read(file_path)?;
     let parsed = Puzzle::reader()
-        .from_file_verbose(path)
+        .from_bytes_verbose(&data)
         .with_context(|| format!("Failed to parse .puz file: {path}"))?;
 
     for warning in &parsed.warnings {
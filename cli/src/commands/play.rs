@@ -0,0 +1,1023 @@
+//! Solve a puzzle interactively in the terminal.
+//!
+//! A full-screen solver: the grid, the Across/Down clue lists when the
+//! terminal is wide enough, a bar with the current clue, and a status line
+//! with the timer. Progress is saved the way `.puz` solving programs save it:
+//! the letters go into the blank grid and the clock into the LTIM section, so
+//! the file can be resumed here or in any other solver. Saving rewrites only
+//! what the solver changes, so sections puz doesn't model are kept.
+//!
+//! Colors follow the global styling setting ([`render::styled`]): with
+//! `--no-color`, `NO_COLOR`, or redirected output, the solver uses only
+//! reverse video and ASCII.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute};
+use crossterm::style::{SetBackgroundColor, SetForegroundColor};
+use crossterm::{cursor, execute, queue, terminal};
+use puz_parse::{Direction, Puzzle, Timer};
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::render;
+
+#[derive(Args)]
+pub(crate) struct PlayArgs {
+    /// the .puz file to solve
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+
+    /// save progress to this file instead of back into FILE
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+const HELP: &str =
+    "^S save  ^Q quit  ^E check word  ^A check all  ^R reveal square  ^W reveal word  ^P pause";
+
+pub(crate) fn run(args: PlayArgs) -> Result<()> {
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        anyhow::bail!("puz play needs an interactive terminal");
+    }
+
+    let data = std::fs::read(&args.file)
+        .with_context(|| format!("failed to read {}", args.file.display()))?;
+    let puzzle = Puzzle::from_bytes(&data)
+        .with_context(|| format!("failed to parse {}", args.file.display()))?;
+    if puzzle.info.is_scrambled {
+        anyhow::bail!(
+            "{} is scrambled; solving it needs the unscrambled solution",
+            args.file.display()
+        );
+    }
+
+    let save_path = args.output.unwrap_or(args.file);
+    let mut game = Game::new(puzzle, data);
+
+    let _guard = TerminalGuard::enter()?;
+    let mut out = std::io::stdout();
+    event_loop(&mut game, &save_path, &mut out)
+}
+
+/// Restores the terminal on drop, including when unwinding from a panic.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(
+            std::io::stdout(),
+            terminal::EnterAlternateScreen,
+            cursor::Hide
+        )?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(
+            std::io::stdout(),
+            cursor::Show,
+            terminal::LeaveAlternateScreen
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn event_loop(game: &mut Game, save_path: &Path, out: &mut impl Write) -> Result<()> {
+    execute!(out, terminal::Clear(terminal::ClearType::All))?;
+    loop {
+        draw(game, out)?;
+        // Wake at least once a second so the clock ticks.
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                if let Action::Quit = game.handle_key(key, save_path) {
+                    return Ok(());
+                }
+            }
+            Event::Resize(..) => execute!(out, terminal::Clear(terminal::ClearType::All))?,
+            _ => {}
+        }
+    }
+}
+
+// --- game state ---
+
+/// One word slot in the grid.
+struct Entry {
+    direction: Direction,
+    number: u16,
+    clue: String,
+    cells: Vec<(usize, usize)>,
+}
+
+enum Action {
+    Continue,
+    Quit,
+}
+
+struct Game {
+    puzzle: Puzzle,
+    /// The file as read, which saves are rewritten from.
+    original: Vec<u8>,
+    width: usize,
+    height: usize,
+    solution: Vec<Vec<char>>,
+    /// The solver's letters: `.` for black squares, `-` for empty ones.
+    fill: Vec<Vec<char>>,
+    numbers: Vec<Vec<Option<u16>>>,
+    circled: Vec<Vec<bool>>,
+    given: Vec<Vec<bool>>,
+    wrong: Vec<Vec<bool>>,
+    revealed: Vec<Vec<bool>>,
+    entries: Vec<Entry>,
+    /// Index into `entries` of the across and down word through each cell.
+    across_at: Vec<Vec<Option<usize>>>,
+    down_at: Vec<Vec<Option<usize>>>,
+    row: usize,
+    col: usize,
+    direction: Direction,
+    /// Time on the clock before the current run of it.
+    elapsed: Duration,
+    /// When the clock was last started; `None` while paused.
+    started: Option<Instant>,
+    solved: bool,
+    unsaved: bool,
+    /// Set by a first quit with unsaved changes; a second quit confirms.
+    quit_armed: bool,
+    message: String,
+}
+
+impl Game {
+    fn new(puzzle: Puzzle, original: Vec<u8>) -> Self {
+        let width = puzzle.info.width as usize;
+        let height = puzzle.info.height as usize;
+        let grid = |rows: &[String]| -> Vec<Vec<char>> {
+            rows.iter().map(|r| r.chars().collect()).collect()
+        };
+        let solution = grid(&puzzle.grid.solution);
+        let mut fill = grid(&puzzle.grid.blank);
+        let flags = |g: &Option<Vec<Vec<bool>>>| {
+            g.clone()
+                .unwrap_or_else(|| vec![vec![false; width]; height])
+        };
+        let circled = flags(&puzzle.extensions.circles);
+        let given = flags(&puzzle.extensions.given);
+
+        // Given squares are shown filled in, whatever the saved grid holds.
+        for (r, row) in given.iter().enumerate() {
+            for (c, &is_given) in row.iter().enumerate() {
+                if is_given {
+                    fill[r][c] = solution[r][c];
+                }
+            }
+        }
+
+        let mut numbers = vec![vec![None; width]; height];
+        let mut across_at = vec![vec![None; width]; height];
+        let mut down_at = vec![vec![None; width]; height];
+        let mut entries = Vec::new();
+        for answer in puzzle.clue_answers() {
+            numbers[answer.row][answer.col] = Some(answer.number);
+            let (dr, dc) = match answer.direction {
+                Direction::Across => (0, 1),
+                Direction::Down => (1, 0),
+            };
+            let mut cells = Vec::new();
+            let (mut r, mut c) = (answer.row, answer.col);
+            while r < height && c < width && fill[r][c] != '.' {
+                let at = match answer.direction {
+                    Direction::Across => &mut across_at,
+                    Direction::Down => &mut down_at,
+                };
+                at[r][c] = Some(entries.len());
+                cells.push((r, c));
+                r += dr;
+                c += dc;
+            }
+            entries.push(Entry {
+                direction: answer.direction,
+                number: answer.number,
+                clue: answer.clue,
+                cells,
+            });
+        }
+
+        let elapsed = puzzle
+            .extensions
+            .timer
+            .map(|t| Duration::from_secs(t.elapsed_secs.into()))
+            .unwrap_or_default();
+
+        let mut game = Self {
+            puzzle,
+            original,
+            width,
+            height,
+            solution,
+            fill,
+            numbers,
+            circled,
+            given,
+            wrong: vec![vec![false; width]; height],
+            revealed: vec![vec![false; width]; height],
+            entries,
+            across_at,
+            down_at,
+            row: 0,
+            col: 0,
+            direction: Direction::Across,
+            elapsed,
+            started: Some(Instant::now()),
+            solved: false,
+            unsaved: false,
+            quit_armed: false,
+            message: String::new(),
+        };
+        if let Some(first) = game.entries.iter().position(|e| !e.cells.is_empty()) {
+            game.select_entry(first);
+        }
+        game.solved = game.is_solved();
+        if game.solved {
+            game.started = None;
+        }
+        game
+    }
+
+    fn is_open(&self, r: usize, c: usize) -> bool {
+        r < self.height && c < self.width && self.fill[r][c] != '.'
+    }
+
+    fn entry_at(&self, direction: Direction, r: usize, c: usize) -> Option<usize> {
+        match direction {
+            Direction::Across => self.across_at[r][c],
+            Direction::Down => self.down_at[r][c],
+        }
+    }
+
+    fn current_entry(&self) -> Option<&Entry> {
+        self.entry_at(self.direction, self.row, self.col)
+            .map(|i| &self.entries[i])
+    }
+
+    fn crossing_entry(&self) -> Option<&Entry> {
+        self.entry_at(other(self.direction), self.row, self.col)
+            .map(|i| &self.entries[i])
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.elapsed + self.started.map(|s| s.elapsed()).unwrap_or_default()
+    }
+
+    // --- movement ---
+
+    fn select(&mut self, r: usize, c: usize) {
+        if !self.is_open(r, c) {
+            return;
+        }
+        self.row = r;
+        self.col = c;
+        // Cells that belong to only one word force that word's direction.
+        if self.entry_at(self.direction, r, c).is_none() {
+            self.direction = other(self.direction);
+        }
+    }
+
+    fn select_entry(&mut self, index: usize) {
+        let entry = &self.entries[index];
+        let target = entry
+            .cells
+            .iter()
+            .find(|&&(r, c)| self.fill[r][c] == '-')
+            .or(entry.cells.first())
+            .copied();
+        if let Some((r, c)) = target {
+            self.direction = entry.direction;
+            self.select(r, c);
+        }
+    }
+
+    fn toggle_direction(&mut self) {
+        if self
+            .entry_at(other(self.direction), self.row, self.col)
+            .is_some()
+        {
+            self.direction = other(self.direction);
+        }
+    }
+
+    /// Arrow keys: an arrow across the current direction first turns the
+    /// cursor, then moves it to the next open square.
+    fn arrow(&mut self, direction: Direction, forward: bool) {
+        if self.direction != direction && self.entry_at(direction, self.row, self.col).is_some() {
+            self.direction = direction;
+            return;
+        }
+        let (dr, dc): (isize, isize) = match (direction, forward) {
+            (Direction::Across, true) => (0, 1),
+            (Direction::Across, false) => (0, -1),
+            (Direction::Down, true) => (1, 0),
+            (Direction::Down, false) => (-1, 0),
+        };
+        let (mut r, mut c) = (self.row as isize, self.col as isize);
+        loop {
+            r += dr;
+            c += dc;
+            if r < 0 || c < 0 || r as usize >= self.height || c as usize >= self.width {
+                return;
+            }
+            if self.is_open(r as usize, c as usize) {
+                self.select(r as usize, c as usize);
+                return;
+            }
+        }
+    }
+
+    /// Step within the current word; at its ends, stay put.
+    fn step(&mut self, forward: bool) {
+        let Some(entry) = self.current_entry() else {
+            return;
+        };
+        let i = entry
+            .cells
+            .iter()
+            .position(|&cell| cell == (self.row, self.col))
+            .unwrap_or(0);
+        let next = if forward {
+            entry.cells.get(i + 1)
+        } else {
+            i.checked_sub(1).and_then(|j| entry.cells.get(j))
+        };
+        if let Some(&(r, c)) = next {
+            self.select(r, c);
+        }
+    }
+
+    fn jump_entry(&mut self, forward: bool) {
+        let Some(current) = self.entry_at(self.direction, self.row, self.col) else {
+            return;
+        };
+        // Across entries then down entries, in number order.
+        let mut order: Vec<usize> = (0..self.entries.len())
+            .filter(|&i| !self.entries[i].cells.is_empty())
+            .collect();
+        order.sort_by_key(|&i| {
+            (
+                self.entries[i].direction == Direction::Down,
+                self.entries[i].number,
+            )
+        });
+        let Some(pos) = order.iter().position(|&i| i == current) else {
+            return;
+        };
+        let next = if forward {
+            order[(pos + 1) % order.len()]
+        } else {
+            order[(pos + order.len() - 1) % order.len()]
+        };
+        self.select_entry(next);
+    }
+
+    // --- filling ---
+
+    fn editable(&self, r: usize, c: usize) -> bool {
+        !self.given[r][c] && !self.revealed[r][c] && !self.solved
+    }
+
+    fn set(&mut self, r: usize, c: usize, ch: char) {
+        if self.editable(r, c) && self.fill[r][c] != ch {
+            self.fill[r][c] = ch;
+            self.wrong[r][c] = false;
+            self.unsaved = true;
+        }
+    }
+
+    fn type_char(&mut self, ch: char) {
+        self.set(self.row, self.col, ch.to_ascii_uppercase());
+        self.step(true);
+        self.after_edit();
+    }
+
+    fn backspace(&mut self) {
+        if self.fill[self.row][self.col] == '-' {
+            self.step(false);
+        }
+        self.set(self.row, self.col, '-');
+    }
+
+    fn after_edit(&mut self) {
+        if !self.solved && self.is_solved() {
+            self.solved = true;
+            self.elapsed = self.elapsed();
+            self.started = None;
+            self.message = format!("Solved in {}!", clock(self.elapsed));
+        }
+    }
+
+    fn is_right(&self, r: usize, c: usize) -> bool {
+        self.fill[r][c].eq_ignore_ascii_case(&self.solution[r][c])
+    }
+
+    fn is_solved(&self) -> bool {
+        (0..self.height)
+            .flat_map(|r| (0..self.width).map(move |c| (r, c)))
+            .filter(|&(r, c)| self.is_open(r, c))
+            .all(|(r, c)| self.is_right(r, c))
+    }
+
+    fn current_cells(&self) -> Vec<(usize, usize)> {
+        self.current_entry()
+            .map(|e| e.cells.clone())
+            .unwrap_or_else(|| vec![(self.row, self.col)])
+    }
+
+    fn all_cells(&self) -> Vec<(usize, usize)> {
+        (0..self.height)
+            .flat_map(|r| (0..self.width).map(move |c| (r, c)))
+            .filter(|&(r, c)| self.is_open(r, c))
+            .collect()
+    }
+
+    fn check(&mut self, cells: Vec<(usize, usize)>) {
+        let mut wrong = 0;
+        for (r, c) in cells {
+            self.wrong[r][c] = self.fill[r][c] != '-' && !self.is_right(r, c);
+            wrong += usize::from(self.wrong[r][c]);
+        }
+        self.message = match wrong {
+            0 => "No mistakes.".to_string(),
+            1 => "1 square is wrong.".to_string(),
+            n => format!("{n} squares are wrong."),
+        };
+    }
+
+    fn reveal(&mut self, cells: Vec<(usize, usize)>) {
+        for (r, c) in cells {
+            if !self.is_right(r, c) && self.editable(r, c) {
+                self.fill[r][c] = self.solution[r][c];
+                self.revealed[r][c] = true;
+                self.wrong[r][c] = false;
+                self.unsaved = true;
+            }
+        }
+        self.after_edit();
+    }
+
+    fn toggle_pause(&mut self) {
+        if self.solved {
+            return;
+        }
+        match self.started.take() {
+            Some(start) => {
+                self.elapsed += start.elapsed();
+                self.message = "Paused.".to_string();
+            }
+            None => {
+                self.started = Some(Instant::now());
+                self.message.clear();
+            }
+        }
+    }
+
+    /// Write the letters into the blank grid and the clock into LTIM.
+    fn save(&mut self, path: &Path) {
+        let mut puzzle = self.puzzle.clone();
+        puzzle.grid.blank = self.fill.iter().map(|row| row.iter().collect()).collect();
+        puzzle.extensions.timer = Some(Timer {
+            elapsed_secs: self.elapsed().as_secs().try_into().unwrap_or(u32::MAX),
+            running: self.started.is_some(),
+        });
+        let written = puz_parse::rewrite(&self.original, &puzzle)
+            .and_then(|bytes| std::fs::write(path, bytes).map_err(Into::into));
+        match written {
+            Ok(()) => {
+                self.unsaved = false;
+                self.message = format!("Saved to {}.", path.display());
+            }
+            Err(e) => self.message = format!("Save failed: {e}"),
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, save_path: &Path) -> Action {
+        let quit_armed = std::mem::take(&mut self.quit_armed);
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Char('c') => {
+                    if !self.unsaved || quit_armed {
+                        return Action::Quit;
+                    }
+                    self.quit_armed = true;
+                    self.message =
+                        "Unsaved changes: ^Q again to quit without saving, ^S to save.".to_string();
+                }
+                KeyCode::Char('s') => self.save(save_path),
+                KeyCode::Char('e') => self.check(self.current_cells()),
+                KeyCode::Char('a') => self.check(self.all_cells()),
+                KeyCode::Char('r') => self.reveal(vec![(self.row, self.col)]),
+                KeyCode::Char('w') => self.reveal(self.current_cells()),
+                KeyCode::Char('p') => self.toggle_pause(),
+                _ => {}
+            }
+            return Action::Continue;
+        }
+
+        if self.started.is_none() && !self.solved {
+            // Any other key resumes a paused clock.
+            self.toggle_pause();
+            return Action::Continue;
+        }
+        if !self.solved {
+            self.message.clear();
+        }
+        match key.code {
+            KeyCode::Left => self.arrow(Direction::Across, false),
+            KeyCode::Right => self.arrow(Direction::Across, true),
+            KeyCode::Up => self.arrow(Direction::Down, false),
+            KeyCode::Down => self.arrow(Direction::Down, true),
+            KeyCode::Char(' ') => self.toggle_direction(),
+            KeyCode::Tab => self.jump_entry(true),
+            KeyCode::BackTab => self.jump_entry(false),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.set(self.row, self.col, '-'),
+            KeyCode::Char(ch) if ch.is_ascii_alphanumeric() => self.type_char(ch),
+            _ => {}
+        }
+        Action::Continue
+    }
+}
+
+fn other(direction: Direction) -> Direction {
+    match direction {
+        Direction::Across => Direction::Down,
+        Direction::Down => Direction::Across,
+    }
+}
+
+fn clock(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+// --- drawing ---
+
+/// How big each grid cell is drawn. The roomy layout has space for clue
+/// numbers and circles; the compact one is used when the terminal is too
+/// small for it.
+#[derive(Clone, Copy)]
+struct CellSize {
+    width: u16,
+    height: u16,
+}
+
+const ROOMY: CellSize = CellSize {
+    width: 4,
+    height: 2,
+};
+const COMPACT: CellSize = CellSize {
+    width: 2,
+    height: 1,
+};
+
+/// Rows used by the header, clue bar, and status line.
+const CHROME_ROWS: u16 = 5;
+/// Narrowest clue panel worth showing beside the grid.
+const MIN_PANEL: u16 = 24;
+
+fn draw(game: &Game, out: &mut impl Write) -> Result<()> {
+    let styled = render::styled();
+    let (cols, rows) = terminal::size()?;
+    let (w, h) = (game.width as u16, game.height as u16);
+
+    let Some(cell) = [ROOMY, COMPACT]
+        .into_iter()
+        .find(|s| w * s.width <= cols && h * s.height + CHROME_ROWS <= rows)
+    else {
+        queue!(
+            out,
+            cursor::MoveTo(0, 0),
+            terminal::Clear(terminal::ClearType::All),
+            Print(format!(
+                "Terminal too small: this puzzle needs at least {}x{}.",
+                w * COMPACT.width,
+                h * COMPACT.height + CHROME_ROWS
+            ))
+        )?;
+        out.flush()?;
+        return Ok(());
+    };
+
+    // Header: title and author, clock on the right.
+    let mut title = game.puzzle.info.title.clone();
+    if !game.puzzle.info.author.is_empty() {
+        title = format!("{title} \u{2014} {}", game.puzzle.info.author);
+    }
+    let clock_text = if game.started.is_none() && !game.solved {
+        format!("{} paused", clock(game.elapsed()))
+    } else {
+        clock(game.elapsed())
+    };
+    let title_width = (cols as usize).saturating_sub(clock_text.len() + 1);
+    queue!(
+        out,
+        cursor::MoveTo(0, 0),
+        SetAttribute(Attribute::Bold),
+        Print(pad(&title, title_width)),
+        Print(" "),
+        Print(clock_text),
+        SetAttribute(Attribute::Reset)
+    )?;
+
+    let grid_top = 2;
+    draw_grid(game, out, cell, grid_top, styled)?;
+
+    let panel_x = w * cell.width + 2;
+    if cols > panel_x + MIN_PANEL {
+        let panel_width = (cols - panel_x).min(60);
+        draw_clue_panel(
+            game,
+            out,
+            panel_x,
+            grid_top,
+            panel_width,
+            rows - CHROME_ROWS,
+        )?;
+    }
+
+    // Clue bar: the current clue, in reverse video.
+    let bar = match game.current_entry() {
+        Some(entry) => format!(
+            " {}{} {} ({})",
+            entry.number,
+            if entry.direction == Direction::Across {
+                "A"
+            } else {
+                "D"
+            },
+            entry.clue,
+            entry.cells.len()
+        ),
+        None => String::new(),
+    };
+    let bar_row = rows - 3;
+    queue!(
+        out,
+        cursor::MoveTo(0, bar_row),
+        SetAttribute(Attribute::Reverse),
+        Print(pad(&bar, cols as usize)),
+        SetAttribute(Attribute::Reset),
+        cursor::MoveTo(0, bar_row + 1),
+        Print(pad(&game.message, cols as usize)),
+        cursor::MoveTo(0, bar_row + 2),
+    )?;
+    queue!(
+        out,
+        Print(render::dim(pad(HELP, (cols as usize).saturating_sub(1)))),
+    )?;
+    out.flush()?;
+    Ok(())
+}
+
+fn draw_grid(
+    game: &Game,
+    out: &mut impl Write,
+    cell: CellSize,
+    top: u16,
+    styled: bool,
+) -> Result<()> {
+    let word = game.current_cells();
+    for r in 0..game.height {
+        for line in 0..cell.height {
+            queue!(out, cursor::MoveTo(0, top + r as u16 * cell.height + line))?;
+            for c in 0..game.width {
+                if !game.is_open(r, c) {
+                    let block = if styled { "\u{2588}" } else { "#" };
+                    queue!(out, Print(block.repeat(cell.width as usize)))?;
+                    continue;
+                }
+
+                let text = cell_text(game, r, c, cell, line, styled);
+                let is_cursor = (r, c) == (game.row, game.col);
+                let in_word = word.contains(&(r, c));
+                if styled {
+                    let (fg, bg) = if is_cursor {
+                        (Color::Black, Color::Yellow)
+                    } else if in_word {
+                        (Color::Black, Color::Cyan)
+                    } else if game.given[r][c] {
+                        (Color::Black, Color::Grey)
+                    } else {
+                        (Color::Black, Color::White)
+                    };
+                    let fg = if line + 1 < cell.height {
+                        fg
+                    } else if game.wrong[r][c] {
+                        Color::DarkRed
+                    } else if game.revealed[r][c] {
+                        Color::DarkMagenta
+                    } else {
+                        fg
+                    };
+                    queue!(
+                        out,
+                        SetForegroundColor(fg),
+                        SetBackgroundColor(bg),
+                        Print(text),
+                        ResetColor
+                    )?;
+                } else if is_cursor {
+                    queue!(
+                        out,
+                        SetAttribute(Attribute::Reverse),
+                        Print(text),
+                        SetAttribute(Attribute::Reset)
+                    )?;
+                } else if in_word {
+                    queue!(
+                        out,
+                        SetAttribute(Attribute::Bold),
+                        Print(text),
+                        SetAttribute(Attribute::Reset)
+                    )?;
+                } else {
+                    queue!(out, Print(text))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The characters for one line of one open cell.
+///
+/// Roomy cells put the clue number on the top line and the letter below it,
+/// with circled squares in parentheses and a trailing `!` for a wrong letter
+/// or `+` for a revealed one. Compact cells are the letter and that marker.
+fn cell_text(game: &Game, r: usize, c: usize, cell: CellSize, line: u16, styled: bool) -> String {
+    let width = cell.width as usize;
+    if line + 1 < cell.height {
+        let number = game.numbers[r][c]
+            .map(|n| n.to_string())
+            .unwrap_or_default();
+        return pad(&number, width);
+    }
+    let letter = match game.fill[r][c] {
+        '-' if styled => '\u{00B7}',
+        '-' => '-',
+        ch => ch,
+    };
+    let marker = if game.wrong[r][c] {
+        '!'
+    } else if game.revealed[r][c] {
+        '+'
+    } else {
+        ' '
+    };
+    if cell.width >= 4 {
+        let (open, close) = if game.circled[r][c] {
+            ('(', ')')
+        } else {
+            (' ', ' ')
+        };
+        format!("{open}{letter}{close}{marker}")
+    } else {
+        let marker = if marker == ' ' && game.circled[r][c] {
+            'o'
+        } else {
+            marker
+        };
+        format!("{letter}{marker}")
+    }
+}
+
+/// The Across list above the Down list, each scrolled to keep the current
+/// word in view. The current word is in reverse video, the crossing one bold.
+fn draw_clue_panel(
+    game: &Game,
+    out: &mut impl Write,
+    x: u16,
+    top: u16,
+    width: u16,
+    height: u16,
+) -> Result<()> {
+    let current = game.current_entry().map(|e| (e.direction, e.number));
+    let crossing = game.crossing_entry().map(|e| (e.direction, e.number));
+    let half = height / 2;
+    for (i, direction) in [Direction::Across, Direction::Down].into_iter().enumerate() {
+        let y0 = top + i as u16 * half;
+        let list_height = half.saturating_sub(1) as usize;
+        let heading = if direction == Direction::Across {
+            "Across"
+        } else {
+            "Down"
+        };
+        queue!(
+            out,
+            cursor::MoveTo(x, y0),
+            SetAttribute(Attribute::Bold),
+            Print(pad(heading, width as usize)),
+            SetAttribute(Attribute::Reset)
+        )?;
+
+        let entries: Vec<&Entry> = game
+            .entries
+            .iter()
+            .filter(|e| e.direction == direction)
+            .collect();
+        let focus = entries
+            .iter()
+            .position(|e| Some((e.direction, e.number)) == current)
+            .or_else(|| {
+                entries
+                    .iter()
+                    .position(|e| Some((e.direction, e.number)) == crossing)
+            })
+            .unwrap_or(0);
+        let start = focus
+            .saturating_sub(list_height / 2)
+            .min(entries.len().saturating_sub(list_height));
+
+        for line in 0..list_height {
+            queue!(out, cursor::MoveTo(x, y0 + 1 + line as u16))?;
+            let Some(entry) = entries.get(start + line) else {
+                queue!(out, Print(pad("", width as usize)))?;
+                continue;
+            };
+            let text = pad(
+                &format!("{:>3} {}", entry.number, entry.clue),
+                width as usize,
+            );
+            let key = Some((entry.direction, entry.number));
+            let attribute = if key == current {
+                Attribute::Reverse
+            } else if key == crossing {
+                Attribute::Bold
+            } else {
+                Attribute::Reset
+            };
+            queue!(
+                out,
+                SetAttribute(attribute),
+                Print(text),
+                SetAttribute(Attribute::Reset)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Truncate or pad `s` to exactly `width` characters.
+fn pad(s: &str, width: usize) -> String {
+    let mut out: String = s.chars().take(width).collect();
+    let len = out.chars().count();
+    out.extend(std::iter::repeat_n(' ', width - len));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Across entries 1, 3, and 5; down entries 1, 2, and 4.
+    fn sample() -> Puzzle {
+        let mut p = Puzzle::new().title("T").grid(["AB.", "CDE", ".FG"]);
+        for (n, clue) in [(1, "one"), (3, "three"), (5, "five")] {
+            p.clues.across.set(n, clue);
+        }
+        for (n, clue) in [(1, "one down"), (2, "two down"), (4, "four down")] {
+            p.clues.down.set(n, clue);
+        }
+        p.extensions.timer = Some(Timer {
+            elapsed_secs: 90,
+            running: true,
+        });
+        p
+    }
+
+    fn game() -> Game {
+        let puzzle = sample();
+        let data = puz_parse::to_bytes(&puzzle).unwrap();
+        Game::new(puzzle, data)
+    }
+
+    fn at(game: &Game) -> (usize, usize, Direction) {
+        (game.row, game.col, game.direction)
+    }
+
+    #[test]
+    fn test_save_round_trip() {
+        let mut game = game();
+        game.type_char('x');
+        game.type_char('b');
+        game.toggle_pause();
+        let path = std::env::temp_dir().join(format!("puz-play-{}.puz", std::process::id()));
+        game.save(&path);
+        let data = std::fs::read(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(!game.unsaved, "{}", game.message);
+        let saved = Puzzle::from_bytes(&data.unwrap()).unwrap();
+        assert_eq!(saved.grid.blank, vec!["XB.", "---", ".--"]);
+        assert_eq!(
+            saved.extensions.timer,
+            Some(Timer {
+                elapsed_secs: 90,
+                running: false,
+            })
+        );
+        assert_eq!(saved.grid.solution, sample().grid.solution);
+        assert_eq!(saved.clues.down.get(4), Some("four down"));
+
+        // Resuming picks up the letters and the clock where they were left.
+        let resumed = Game::new(saved, Vec::new());
+        assert_eq!(resumed.fill[0], vec!['X', 'B', '.']);
+        assert_eq!(resumed.elapsed, Duration::from_secs(90));
+    }
+
+    #[test]
+    fn test_typing_moves_within_the_word() {
+        let mut game = game();
+        assert_eq!(at(&game), (0, 0, Direction::Across));
+        game.type_char('a');
+        assert_eq!(at(&game), (0, 1, Direction::Across));
+        // At the end of the word the cursor stays put.
+        game.type_char('b');
+        assert_eq!(at(&game), (0, 1, Direction::Across));
+        assert!(game.unsaved);
+
+        game.backspace();
+        assert_eq!(game.fill[0][1], '-');
+        game.backspace();
+        assert_eq!(at(&game), (0, 0, Direction::Across));
+        assert_eq!(game.fill[0][0], '-');
+    }
+
+    #[test]
+    fn test_arrows_and_direction() {
+        let mut game = game();
+        // An arrow across the current direction turns the cursor first.
+        game.arrow(Direction::Down, true);
+        assert_eq!(at(&game), (0, 0, Direction::Down));
+        game.arrow(Direction::Down, true);
+        assert_eq!(at(&game), (1, 0, Direction::Down));
+        game.toggle_direction();
+        assert_eq!(at(&game), (1, 0, Direction::Across));
+
+        // Black squares are skipped, and the edge stops the cursor.
+        game.arrow(Direction::Across, true);
+        game.arrow(Direction::Across, true);
+        game.arrow(Direction::Across, true);
+        assert_eq!(at(&game), (1, 2, Direction::Across));
+        game.arrow(Direction::Down, false);
+        game.arrow(Direction::Down, false);
+        assert_eq!(at(&game), (1, 2, Direction::Down));
+    }
+
+    #[test]
+    fn test_tab_cycles_entries() {
+        let mut game = game();
+        game.jump_entry(true);
+        assert_eq!(at(&game), (1, 0, Direction::Across));
+        game.jump_entry(false);
+        game.jump_entry(false);
+        // Backwards from 1 Across wraps around to 4 Down.
+        assert_eq!(at(&game), (1, 2, Direction::Down));
+        game.jump_entry(true);
+        assert_eq!(at(&game), (0, 0, Direction::Across));
+    }
+
+    #[test]
+    fn test_check_and_reveal() {
+        let mut game = game();
+        game.type_char('a');
+        game.type_char('x');
+        game.check(game.current_cells());
+        assert_eq!(game.message, "1 square is wrong.");
+        assert!(game.wrong[0][1] && !game.wrong[0][0]);
+
+        game.reveal(game.current_cells());
+        assert_eq!(game.fill[0][1], 'B');
+        assert!(game.revealed[0][1] && !game.wrong[0][1]);
+        // A correct square isn't marked as revealed, and revealed ones stay.
+        assert!(!game.revealed[0][0]);
+        game.set(0, 1, 'Z');
+        assert_eq!(game.fill[0][1], 'B');
+
+        game.check(game.all_cells());
+        assert_eq!(game.message, "No mistakes.");
+        game.reveal(game.all_cells());
+        assert!(game.solved);
+        assert!(game.started.is_none());
+        assert!(game.message.starts_with("Solved in "), "{}", game.message);
+    }
+}
@@ -0,0 +1,369 @@
+//! Render a puzzle as a printable, newspaper-style PDF.
+//!
+//! The first page carries the header (title, author, copyright), the numbered
+//! grid in the top-right, and the Across/Down clues flowing through three
+//! columns; clues that don't fit continue on further pages. `--answers` adds
+//! an answer-key page. The PDF itself is produced by [`crate::pdf`].
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use puz_parse::{ClueAnswer, Direction, Puzzle};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::commands::{answer_cells, cell_solution, circled, given};
+use crate::input;
+use crate::pdf::{self, Document, Font, Page, PageSize};
+
+#[derive(Args)]
+pub(crate) struct PrintArgs {
+    /// the .puz file to print (- for stdin)
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+
+    /// where to write the PDF (- for stdout)
+    #[arg(short, long, value_name = "FILE")]
+    output: PathBuf,
+
+    /// add an answer-key page
+    #[arg(long)]
+    answers: bool,
+
+    /// paper size
+    #[arg(long, value_enum, default_value_t = Paper::Letter)]
+    paper: Paper,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Paper {
+    Letter,
+    A4,
+}
+
+impl Paper {
+    fn size(self) -> PageSize {
+        match self {
+            Paper::Letter => pdf::LETTER,
+            Paper::A4 => pdf::A4,
+        }
+    }
+}
+
+const MARGIN: f32 = 36.0;
+const GUTTER: f32 = 14.0;
+const COLUMNS: usize = 3;
+
+const TITLE_SIZE: f32 = 16.0;
+const BYLINE_SIZE: f32 = 10.0;
+const COPYRIGHT_SIZE: f32 = 8.0;
+const HEADING_SIZE: f32 = 11.0;
+const CLUE_SIZE: f32 = 9.0;
+const CLUE_LEADING: f32 = 11.0;
+/// Largest grid cell, so small puzzles don't print comically large.
+const MAX_CELL: f32 = 28.0;
+/// Gray level for given (pre-filled) squares.
+const GIVEN_GRAY: f32 = 0.85;
+
+pub(crate) fn run(args: PrintArgs) -> Result<()> {
+    let data = input::read(&args.file)?;
+    // Parse leniently: a bad checksum shouldn't stop anyone printing a puzzle.
+    let parsed = Puzzle::reader()
+        .from_bytes_verbose(&data)
+        .with_context(|| format!("failed to parse {}", args.file.display()))?;
+    for warning in &parsed.warnings {
+        eprintln!("Warning in {}: {warning}", args.file.display());
+    }
+    let puzzle = parsed.result;
+
+    let mut doc = Document::new(args.paper.size());
+    let entries = puzzle.clue_answers();
+    layout_puzzle(&mut doc, &puzzle, &entries);
+    if args.answers {
+        layout_answer_key(&mut doc, &puzzle, &entries);
+    }
+
+    input::write(&args.output, doc.to_bytes())?;
+    Ok(())
+}
+
+/// A rectangular region of a page that text flows down through.
+#[derive(Clone, Copy)]
+struct Column {
+    x: f32,
+    top: f32,
+    bottom: f32,
+    width: f32,
+}
+
+/// One thing to place in a column.
+enum Item {
+    /// A bold section heading ("Across", "Down").
+    Heading(String),
+    /// A numbered entry: the number is set in bold, the text wraps with a
+    /// hanging indent.
+    Entry { number: u16, text: String },
+}
+
+fn layout_puzzle(doc: &mut Document, puzzle: &Puzzle, entries: &[ClueAnswer]) {
+    let size = doc.size();
+    let column_width = column_width(size);
+    let page = doc.add_page();
+
+    let header_bottom = draw_header(page, size, puzzle, &puzzle.info.title);
+
+    // The grid spans the two right-hand columns, sized to fit the width and at
+    // most ~55% of the remaining height.
+    let grid_left = MARGIN + column_width + GUTTER;
+    let grid_area_width = size.width - MARGIN - grid_left;
+    let grid_area_height = (header_bottom - MARGIN) * 0.55;
+    let width = puzzle.info.width as f32;
+    let height = puzzle.info.height as f32;
+    let cell = (grid_area_width / width)
+        .min(grid_area_height / height)
+        .min(MAX_CELL);
+    let grid_x = size.width - MARGIN - cell * width;
+    let grid_top = header_bottom;
+    draw_grid(page, puzzle, entries, grid_x, grid_top, cell, false);
+    let grid_bottom = grid_top - cell * height;
+
+    let items = clue_items(entries, |e| e.clue.clone());
+    flow(
+        doc,
+        first_page_columns(size, header_bottom, grid_bottom),
+        &items,
+    );
+}
+
+fn layout_answer_key(doc: &mut Document, puzzle: &Puzzle, entries: &[ClueAnswer]) {
+    let size = doc.size();
+    let column_width = column_width(size);
+    let page = doc.add_page();
+
+    let title = if puzzle.info.title.is_empty() {
+        "Answers".to_string()
+    } else {
+        format!("{} \u{2014} Answers", puzzle.info.title)
+    };
+    let header_bottom = draw_header(page, size, puzzle, &title);
+
+    // A smaller filled grid across the top-right, as on the puzzle page.
+    let grid_area_width = 2.0 * column_width + GUTTER;
+    let grid_area_height = (header_bottom - MARGIN) * 0.45;
+    let width = puzzle.info.width as f32;
+    let height = puzzle.info.height as f32;
+    let cell = (grid_area_width / width)
+        .min(grid_area_height / height)
+        .min(MAX_CELL * 0.75);
+    let grid_x = size.width - MARGIN - cell * width;
+    draw_grid(page, puzzle, entries, grid_x, header_bottom, cell, true);
+    let grid_bottom = header_bottom - cell * height;
+
+    let items = clue_items(entries, |e| answer_cells(puzzle, e).concat());
+    flow(
+        doc,
+        first_page_columns(size, header_bottom, grid_bottom),
+        &items,
+    );
+}
+
+/// The Across then Down entries as flowable items, with `text` choosing what
+/// each entry shows (its clue or its answer).
+fn clue_items(entries: &[ClueAnswer], text: impl Fn(&ClueAnswer) -> String) -> Vec<Item> {
+    let mut items = Vec::new();
+    for (heading, direction) in [("Across", Direction::Across), ("Down", Direction::Down)] {
+        items.push(Item::Heading(heading.to_string()));
+        items.extend(
+            entries
+                .iter()
+                .filter(|e| e.direction == direction)
+                .map(|e| Item::Entry {
+                    number: e.number,
+                    text: text(e),
+                }),
+        );
+    }
+    items
+}
+
+/// Columns on a page whose grid occupies the top of the two right-hand
+/// columns: the left column runs the full height, the others start below the
+/// grid.
+fn first_page_columns(size: PageSize, header_bottom: f32, grid_bottom: f32) -> Vec<Column> {
+    let width = column_width(size);
+    (0..COLUMNS)
+        .map(|i| Column {
+            x: MARGIN + i as f32 * (width + GUTTER),
+            top: if i == 0 {
+                header_bottom
+            } else {
+                grid_bottom - 2.0 * GUTTER
+            },
+            bottom: MARGIN,
+            width,
+        })
+        .collect()
+}
+
+fn column_width(size: PageSize) -> f32 {
+    (size.width - 2.0 * MARGIN - (COLUMNS - 1) as f32 * GUTTER) / COLUMNS as f32
+}
+
+/// Draw the title, byline, and copyright; return the y just below them.
+fn draw_header(page: &mut Page, size: PageSize, puzzle: &Puzzle, title: &str) -> f32 {
+    let mut y = size.height - MARGIN - TITLE_SIZE;
+    page.text(MARGIN, y, Font::Bold, TITLE_SIZE, title);
+    if !puzzle.info.author.is_empty() {
+        y -= BYLINE_SIZE + 6.0;
+        page.text(MARGIN, y, Font::Regular, BYLINE_SIZE, &puzzle.info.author);
+    }
+    if !puzzle.info.copyright.is_empty() {
+        y -= COPYRIGHT_SIZE + 4.0;
+        page.text(
+            MARGIN,
+            y,
+            Font::Regular,
+            COPYRIGHT_SIZE,
+            &puzzle.info.copyright,
+        );
+    }
+    y -= 8.0;
+    page.line(MARGIN, y, size.width - MARGIN, y, 0.75);
+    y - GUTTER
+}
+
+/// Draw the grid with its top-left corner at `(x, top)`.
+///
+/// Black squares are filled, given squares shaded, circled squares circled,
+/// and numbered cells numbered. With `solved`, each cell also shows its
+/// solution (the full rebus text where there is one).
+fn draw_grid(
+    page: &mut Page,
+    puzzle: &Puzzle,
+    entries: &[ClueAnswer],
+    x: f32,
+    top: f32,
+    cell: f32,
+    solved: bool,
+) {
+    let numbers: HashMap<(usize, usize), u16> =
+        entries.iter().map(|e| ((e.row, e.col), e.number)).collect();
+
+    let number_size = (cell * 0.3).max(4.0);
+    for (r, row) in puzzle.grid.blank.iter().enumerate() {
+        for (c, ch) in row.chars().enumerate() {
+            let cx = x + c as f32 * cell;
+            let cy = top - (r + 1) as f32 * cell;
+            if ch == '.' {
+                page.fill_rect(cx, cy, cell, cell, 0.0);
+                continue;
+            }
+            if given(puzzle, r, c) {
+                page.fill_rect(cx, cy, cell, cell, GIVEN_GRAY);
+            }
+            page.stroke_rect(cx, cy, cell, cell, 0.5);
+            if circled(puzzle, r, c) {
+                page.stroke_circle(cx + cell / 2.0, cy + cell / 2.0, cell / 2.0 - 1.0, 0.4);
+            }
+            if let Some(n) = numbers.get(&(r, c)) {
+                page.text(
+                    cx + 1.5,
+                    cy + cell - number_size - 0.5,
+                    Font::Regular,
+                    number_size,
+                    &n.to_string(),
+                );
+            }
+            if solved {
+                let text = cell_solution(puzzle, r, c);
+                // Shrink long rebus entries to fit the cell.
+                let mut font_size = cell * 0.55;
+                let max_width = cell - 2.0;
+                let width = pdf::text_width(&text, Font::Bold, font_size);
+                if width > max_width {
+                    font_size *= max_width / width;
+                }
+                page.text_centered(
+                    cx + cell / 2.0,
+                    cy + cell * 0.2,
+                    Font::Bold,
+                    font_size,
+                    &text,
+                );
+            }
+        }
+    }
+    let width = puzzle.info.width as f32 * cell;
+    let height = puzzle.info.height as f32 * cell;
+    page.stroke_rect(x, top - height, width, height, 1.5);
+}
+
+/// Flow `items` down `columns`, adding pages of full-height columns when the
+/// given ones fill up.
+fn flow(doc: &mut Document, first_columns: Vec<Column>, items: &[Item]) {
+    let size = doc.size();
+    let width = column_width(size);
+    let number_width = pdf::text_width("000", Font::Bold, CLUE_SIZE) + 3.0;
+
+    let mut columns = first_columns;
+    let mut index = 0;
+    let mut y = columns[0].top;
+    let mut page_index = doc.page_count() - 1;
+
+    for item in items {
+        let (lines, height, gap) = match item {
+            Item::Heading(_) => (Vec::new(), HEADING_SIZE + 4.0, 6.0),
+            Item::Entry { text, .. } => {
+                let lines = pdf::wrap(text, Font::Regular, CLUE_SIZE, width - number_width);
+                let height = lines.len() as f32 * CLUE_LEADING;
+                (lines, height, 2.0)
+            }
+        };
+
+        // Move to the next column (or page) if this item doesn't fit. An item
+        // taller than a whole column is placed anyway rather than looping.
+        while y - height < columns[index].bottom && y < columns[index].top {
+            index += 1;
+            if index == columns.len() {
+                doc.add_page();
+                page_index += 1;
+                columns = (0..COLUMNS)
+                    .map(|i| Column {
+                        x: MARGIN + i as f32 * (width + GUTTER),
+                        top: size.height - MARGIN,
+                        bottom: MARGIN,
+                        width,
+                    })
+                    .collect();
+                index = 0;
+            }
+            y = columns[index].top;
+        }
+
+        let column = columns[index];
+        let page = doc.page_mut(page_index);
+        match item {
+            Item::Heading(text) => {
+                y -= HEADING_SIZE;
+                page.text(column.x, y, Font::Bold, HEADING_SIZE, text);
+                y -= 4.0;
+                page.line(column.x, y, column.x + column.width, y, 0.5);
+            }
+            Item::Entry { number, .. } => {
+                for (i, line) in lines.iter().enumerate() {
+                    y -= CLUE_LEADING;
+                    if i == 0 {
+                        page.text_right(
+                            column.x + number_width - 3.0,
+                            y,
+                            Font::Bold,
+                            CLUE_SIZE,
+                            &number.to_string(),
+                        );
+                    }
+                    page.text(column.x + number_width, y, Font::Regular, CLUE_SIZE, line);
+                }
+            }
+        }
+        y -= gap;
+    }
+}
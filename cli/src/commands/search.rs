@@ -0,0 +1,356 @@
+//! Search a directory of puzzles for clues or answers.
+//!
+//! Without an index every file is parsed on each run. With `--index`, the
+//! corpus is loaded once into the SQLite database `export --format sqlite`
+//! writes, and later searches scan that instead. The index records the
+//! directory and each file's size and modification time, and is rebuilt when
+//! they no longer match.
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use comfy_table::{Cell, CellAlignment};
+use puz_parse::Puzzle;
+use regex::Regex;
+use rusqlite::Connection;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::types::Value;
+use std::path::{Path, PathBuf};
+
+use crate::commands::{export, to_stdout};
+use crate::input;
+use crate::render;
+
+#[derive(Args)]
+pub(crate) struct SearchArgs {
+    /// directory to search recursively for .puz files and archives
+    #[arg(value_name = "DIR")]
+    dir: PathBuf,
+
+    /// text to look for (case-insensitive)
+    #[arg(value_name = "QUERY")]
+    query: String,
+
+    /// treat QUERY as a regular expression
+    #[arg(long, conflicts_with = "pattern")]
+    regex: bool,
+
+    /// treat QUERY as an answer pattern, `?` for any one square (e.g. ?A?E)
+    #[arg(long)]
+    pattern: bool,
+
+    /// which text to search
+    #[arg(long = "in", value_enum, default_value_t = Target::Both)]
+    target: Target,
+
+    /// search a SQLite index of DIR, building it first if FILE doesn't exist or is out of date
+    #[arg(long, value_name = "FILE")]
+    index: Option<PathBuf>,
+
+    /// rebuild the --index from DIR even if it exists
+    #[arg(long, requires = "index")]
+    rebuild_index: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Target {
+    Clue,
+    Answer,
+    Both,
+}
+
+/// How QUERY is matched against a clue or answer.
+enum Matcher {
+    /// Lowercased needle.
+    Substring(String),
+    Regex(Regex),
+    /// Uppercased pattern; `None` is a `?` wildcard.
+    Pattern(Vec<Option<char>>),
+}
+
+impl Matcher {
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            Matcher::Substring(needle) => text.to_lowercase().contains(needle),
+            Matcher::Regex(regex) => regex.is_match(text),
+            Matcher::Pattern(pattern) => {
+                text.chars().count() == pattern.len()
+                    && text
+                        .chars()
+                        .zip(pattern)
+                        .all(|(c, p)| p.is_none_or(|p| c.to_ascii_uppercase() == p))
+            }
+        }
+    }
+}
+
+/// One matching entry.
+struct Hit {
+    file: String,
+    number: u16,
+    direction: String,
+    clue: String,
+    answer: String,
+}
+
+pub(crate) fn run(args: SearchArgs) -> Result<()> {
+    let matcher = if args.pattern {
+        Matcher::Pattern(
+            args.query
+                .chars()
+                .map(|c| (c != '?').then(|| c.to_ascii_uppercase()))
+                .collect(),
+        )
+    } else if args.regex {
+        // Case-insensitive, like the plain search.
+        Matcher::Regex(Regex::new(&format!("(?i){}", args.query)).context("invalid QUERY regex")?)
+    } else {
+        Matcher::Substring(args.query.to_lowercase())
+    };
+    // Patterns describe answers, so they never match clue text.
+    let target = if args.pattern {
+        Target::Answer
+    } else {
+        args.target
+    };
+
+    let hits = match &args.index {
+        Some(index) => {
+            let stale = !args.rebuild_index
+                && index.exists()
+                && !export::sqlite_is_current(index, &args.dir)?;
+            if stale {
+                eprintln!(
+                    "index {} is not a full, current index of {}",
+                    index.display(),
+                    args.dir.display()
+                );
+            }
+            if args.rebuild_index || stale || !index.exists() {
+                eprintln!("building index {} ...", index.display());
+                export::build_sqlite(&args.dir, index)?;
+            }
+            search_index(index, matcher, target)
+                .with_context(|| format!("failed to search index {}", index.display()))?
+        }
+        None => search_files(&args.dir, &matcher, target)?,
+    };
+
+    if hits.is_empty() {
+        eprintln!("no matches");
+        return Ok(());
+    }
+    let mut table = render::borderless_table();
+    table.set_header(vec!["file", "num", "dir", "clue", "answer"]);
+    for hit in &hits {
+        table.add_row(vec![
+            Cell::new(&hit.file),
+            Cell::new(hit.number).set_alignment(CellAlignment::Right),
+            Cell::new(&hit.direction),
+            Cell::new(&hit.clue),
+            Cell::new(&hit.answer),
+        ]);
+    }
+    to_stdout(|out| Ok(writeln!(out, "{table}")?))?;
+    eprintln!("{} matches", hits.len());
+    Ok(())
+}
+
+fn keeps(matcher: &Matcher, target: Target, clue: &str, answer: &str) -> bool {
+    (target != Target::Answer && matcher.is_match(clue))
+        || (target != Target::Clue && matcher.is_match(answer))
+}
+
+fn search_files(dir: &Path, matcher: &Matcher, target: Target) -> Result<Vec<Hit>> {
+    let files = input::find(dir)?;
+
+    let mut hits = Vec::new();
+    for input in input::expand(files) {
+        // Unreadable or unparseable files can't contain a match; skip them
+        // quietly, as a search isn't a validation pass.
+        let Ok(data) = input.read() else {
+            continue;
+        };
+        let Ok(puzzle) = Puzzle::from_bytes(&data) else {
+            continue;
+        };
+        for entry in puzzle.clue_answers() {
+            if keeps(matcher, target, &entry.clue, &entry.answer) {
+                hits.push(Hit {
+                    file: input.name().into_owned(),
+                    number: entry.number,
+                    direction: export::direction_name(entry.direction).to_string(),
+                    clue: entry.clue,
+                    answer: entry.answer,
+                });
+            }
+        }
+    }
+    Ok(hits)
+}
+
+/// SQL conditions implied by `matcher`, to narrow the rows before `puz_keeps`
+/// checks each one exactly. They are never stricter than the matcher, and let
+/// SQLite use the length index and skip most rows without calling into Rust.
+fn prefilter(matcher: &Matcher, target: Target) -> (String, Vec<Value>) {
+    // LIKE folds case for ASCII only, so text with other characters can't be
+    // pushed down without missing matches the Rust matcher would find.
+    let escape = |text: &str| {
+        text.replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    };
+    match matcher {
+        Matcher::Substring(needle) if needle.is_ascii() => {
+            let column = |name| format!("{name} LIKE '%' || ?1 || '%' ESCAPE '\\'");
+            let sql = match target {
+                Target::Clue => column("e.clue"),
+                Target::Answer => column("e.answer"),
+                Target::Both => format!("({} OR {})", column("e.clue"), column("e.answer")),
+            };
+            (sql, vec![Value::Text(escape(needle))])
+        }
+        Matcher::Pattern(pattern) => {
+            let length = Value::Integer(pattern.len() as i64);
+            if pattern.iter().flatten().all(char::is_ascii) {
+                let text = pattern
+                    .iter()
+                    .map(|p| p.map_or_else(|| "_".to_string(), |c| escape(&c.to_string())))
+                    .collect::<String>();
+                let sql = "e.length = ?1 AND e.answer LIKE ?2 ESCAPE '\\'".to_string();
+                (sql, vec![length, Value::Text(text)])
+            } else {
+                ("e.length = ?1".to_string(), vec![length])
+            }
+        }
+        _ => ("1".to_string(), Vec::new()),
+    }
+}
+
+fn search_index(index: &Path, matcher: Matcher, target: Target) -> Result<Vec<Hit>> {
+    let conn = Connection::open(index)?;
+    let (narrow, params) = prefilter(&matcher, target);
+    // Matching runs in Rust through a SQL function, so an indexed search has
+    // exactly the semantics of a file scan.
+    conn.create_scalar_function(
+        "puz_keeps",
+        2,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        move |ctx| {
+            let clue = ctx.get_raw(0).as_str().unwrap_or_default();
+            let answer = ctx.get_raw(1).as_str().unwrap_or_default();
+            Ok(keeps(&matcher, target, clue, answer))
+        },
+    )?;
+
+    // Rows were inserted in sorted file order, so rowid order matches the
+    // order of a file scan.
+    let mut query = conn.prepare(&format!(
+        "SELECT p.file, e.number, e.direction, e.clue, e.answer
+         FROM entries e JOIN puzzles p ON p.id = e.puzzle_id
+         WHERE {narrow} AND puz_keeps(e.clue, e.answer)
+         ORDER BY e.rowid"
+    ))?;
+    let hits = query
+        .query_map(rusqlite::params_from_iter(params), |row| {
+            Ok(Hit {
+                file: row.get(0)?,
+                number: row.get(1)?,
+                direction: row.get(2)?,
+                clue: row.get(3)?,
+                answer: row.get(4)?,
+            })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(hits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(text: &str) -> Matcher {
+        Matcher::Pattern(text.chars().map(|c| (c != '?').then_some(c)).collect())
+    }
+
+    /// The answers among `answers` that `matcher`'s prefilter lets through,
+    /// run against an in-memory table shaped like the export's.
+    fn narrowed(matcher: &Matcher, answers: &[&str]) -> Vec<String> {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE entries (clue TEXT, answer TEXT, length INTEGER)")
+            .unwrap();
+        for answer in answers {
+            conn.execute(
+                "INSERT INTO entries VALUES ('', ?1, ?2)",
+                rusqlite::params![answer, answer.chars().count()],
+            )
+            .unwrap();
+        }
+        let (narrow, params) = prefilter(matcher, Target::Answer);
+        let mut query = conn
+            .prepare(&format!("SELECT answer FROM entries e WHERE {narrow}"))
+            .unwrap();
+        query
+            .query_map(rusqlite::params_from_iter(params), |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_is_match() {
+        let substring = Matcher::Substring("ow".to_string());
+        assert!(substring.is_match("Big OWL"));
+        assert!(!substring.is_match("O W"));
+
+        let regex = Matcher::Regex(Regex::new("(?i)^o.l$").unwrap());
+        assert!(regex.is_match("OWL"));
+        assert!(!regex.is_match("OWLS"));
+
+        let owl = pattern("O?L");
+        assert!(owl.is_match("OWL"));
+        assert!(owl.is_match("owl"));
+        assert!(!owl.is_match("OWLS"));
+        assert!(!owl.is_match("AWL"));
+        // Length counts characters, not bytes.
+        assert!(pattern("?É").is_match("ÉÉ"));
+    }
+
+    #[test]
+    fn test_prefilter_escapes_like_wildcards() {
+        let (sql, params) = prefilter(&Matcher::Substring("5%_\\".to_string()), Target::Both);
+        assert_eq!(
+            sql,
+            "(e.clue LIKE '%' || ?1 || '%' ESCAPE '\\' \
+             OR e.answer LIKE '%' || ?1 || '%' ESCAPE '\\')"
+        );
+        assert_eq!(params, [Value::Text("5\\%\\_\\\\".to_string())]);
+
+        let answers = ["100%", "1000", "A_B", "AXB", "C\\D", "CD"];
+        let percent = Matcher::Substring("0%".to_string());
+        assert_eq!(narrowed(&percent, &answers), ["100%"]);
+        assert_eq!(narrowed(&pattern("A_B"), &answers), ["A_B"]);
+        assert_eq!(narrowed(&pattern("C\\?"), &answers), ["C\\D"]);
+        // `?` is the only wildcard, and it's a LIKE `_`.
+        assert_eq!(narrowed(&pattern("A?B"), &answers), ["A_B", "AXB"]);
+    }
+
+    #[test]
+    fn test_prefilter_non_ascii_falls_back() {
+        // LIKE wouldn't fold É to é, so the whole scan goes to `is_match`.
+        let (sql, params) = prefilter(&Matcher::Substring("é".to_string()), Target::Clue);
+        assert_eq!((sql.as_str(), params.len()), ("1", 0));
+
+        // A pattern still narrows by length.
+        let cafe = pattern("CAF\u{c9}");
+        let (sql, params) = prefilter(&cafe, Target::Answer);
+        assert_eq!(sql, "e.length = ?1");
+        assert_eq!(params, [Value::Integer(4)]);
+        assert_eq!(
+            narrowed(&cafe, &["CAFÉ", "CAFE", "CAFES"]),
+            ["CAFÉ", "CAFE"]
+        );
+
+        let regex = Matcher::Regex(Regex::new("x").unwrap());
+        assert_eq!(prefilter(&regex, Target::Both).0, "1");
+    }
+}
@@ -0,0 +1,197 @@
+//! Show a puzzle the way a solver sees it: a boxed grid and the clue lists.
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use comfy_table::{Cell, CellAlignment};
+use puz_parse::{Direction, Puzzle};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::commands::{cell_solution, circled, given, to_stdout};
+use crate::input;
+use crate::render;
+
+#[derive(Args)]
+pub(crate) struct ShowArgs {
+    /// the .puz file to show (- for stdin)
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+
+    /// what to put in the grid's squares
+    #[arg(long, value_enum, default_value_t = Mode::Solution)]
+    mode: Mode,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Mode {
+    /// the solution, with rebus squares spelled out
+    Solution,
+    /// the solver's progress saved in the file
+    Fill,
+    /// empty squares, ready to solve
+    Empty,
+}
+
+pub(crate) fn run(args: ShowArgs) -> Result<()> {
+    let data = input::read(&args.file)?;
+    let parsed = Puzzle::reader()
+        .from_bytes_verbose(&data)
+        .with_context(|| format!("failed to parse {}", args.file.display()))?;
+    for warning in &parsed.warnings {
+        eprintln!("Warning in {}: {warning}", args.file.display());
+    }
+    to_stdout(|out| print(out, &parsed.result, args.mode))
+}
+
+fn print(out: &mut dyn Write, puzzle: &Puzzle, mode: Mode) -> Result<()> {
+    writeln!(out, "{}", render::bold(&puzzle.info.title))?;
+    if !puzzle.info.author.is_empty() {
+        writeln!(out, "{}", puzzle.info.author)?;
+    }
+    if !puzzle.info.copyright.is_empty() {
+        writeln!(out, "{}", render::dim(&puzzle.info.copyright))?;
+    }
+    writeln!(out)?;
+
+    let lines = grid_lines(puzzle, mode, render::styled());
+    writeln!(out, "{}", lines.join("\n"))?;
+
+    if !puzzle.info.notes.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "{}", render::dim(&puzzle.info.notes))?;
+    }
+
+    let entries = puzzle.clue_answers();
+    for (heading, direction) in [("Across", Direction::Across), ("Down", Direction::Down)] {
+        writeln!(out)?;
+        writeln!(out, "{}", render::bold(heading))?;
+        let mut table = render::borderless_table();
+        for entry in entries.iter().filter(|e| e.direction == direction) {
+            table.add_row(vec![
+                Cell::new(entry.number).set_alignment(CellAlignment::Right),
+                Cell::new(&entry.clue),
+            ]);
+        }
+        writeln!(out, "{table}")?;
+    }
+    Ok(())
+}
+
+/// Border characters for the grid, Unicode box drawing or plain ASCII.
+struct Borders {
+    horizontal: &'static str,
+    vertical: char,
+    /// Corners and junctions, indexed by `[row position][column position]`
+    /// where each position is 0 = first, 1 = middle, 2 = last.
+    joints: [[char; 3]; 3],
+    block: char,
+}
+
+const UNICODE: Borders = Borders {
+    horizontal: "\u{2500}\u{2500}\u{2500}",
+    vertical: '\u{2502}',
+    joints: [
+        ['\u{250C}', '\u{252C}', '\u{2510}'],
+        ['\u{251C}', '\u{253C}', '\u{2524}'],
+        ['\u{2514}', '\u{2534}', '\u{2518}'],
+    ],
+    block: '\u{2588}',
+};
+
+const ASCII: Borders = Borders {
+    horizontal: "---",
+    vertical: '|',
+    joints: [['+'; 3]; 3],
+    block: '#',
+};
+
+/// Render the grid as lines of text. Each square is three characters wide
+/// and two lines tall: the clue number on top, the letter(s) below. Circled
+/// squares put the letter in parentheses; given squares are shaded (or, in
+/// ASCII, padded with `:`).
+///
+/// `styled` selects Unicode borders and shading; without it the grid is plain
+/// ASCII. The `text` exporter uses the ASCII form.
+pub(crate) fn grid_lines(puzzle: &Puzzle, mode: Mode, styled: bool) -> Vec<String> {
+    let borders = if styled { &UNICODE } else { &ASCII };
+    let width = puzzle.info.width as usize;
+    let height = puzzle.info.height as usize;
+
+    let mut numbers = vec![vec![None; width]; height];
+    for entry in puzzle.clue_answers() {
+        numbers[entry.row][entry.col] = Some(entry.number);
+    }
+    let blank: Vec<Vec<char>> = puzzle
+        .grid
+        .blank
+        .iter()
+        .map(|r| r.chars().collect())
+        .collect();
+
+    let position = |i: usize, len: usize| {
+        if i == 0 {
+            0
+        } else if i == len {
+            2
+        } else {
+            1
+        }
+    };
+    let rule = |row: usize| {
+        let joints = borders.joints[position(row, height)];
+        let mut line = String::new();
+        for c in 0..width {
+            line.push(joints[position(c, width)]);
+            line.push_str(borders.horizontal);
+        }
+        line.push(joints[2]);
+        line
+    };
+
+    let mut lines = vec![rule(0)];
+    for r in 0..height {
+        let mut top = String::new();
+        let mut bottom = String::new();
+        for c in 0..width {
+            top.push(borders.vertical);
+            bottom.push(borders.vertical);
+            if blank[r][c] == '.' {
+                let block: String = std::iter::repeat_n(borders.block, 3).collect();
+                top.push_str(&block);
+                bottom.push_str(&block);
+                continue;
+            }
+
+            let number = numbers[r][c].map(|n| n.to_string()).unwrap_or_default();
+            let content = match mode {
+                Mode::Solution => cell_solution(puzzle, r, c),
+                Mode::Fill if blank[r][c] != '-' => blank[r][c].to_string(),
+                _ => String::new(),
+            };
+            let is_circled = circled(puzzle, r, c);
+            let is_given = given(puzzle, r, c);
+
+            let pad = if is_given && !styled { ':' } else { ' ' };
+            let mut letters = match content.chars().count() {
+                0 | 1 if is_circled => format!("({})", content.chars().next().unwrap_or(' ')),
+                0 => pad.to_string().repeat(3),
+                1 => format!("{pad}{content}{pad}"),
+                // A rebus longer than the square shows its first letters.
+                _ => format!("{:<3}", content.chars().take(3).collect::<String>()),
+            };
+            let mut number_line = format!("{number:<3}");
+            if is_given && styled {
+                letters = render::shaded(letters);
+                number_line = render::shaded(number_line);
+            }
+            top.push_str(&number_line);
+            bottom.push_str(&letters);
+        }
+        top.push(borders.vertical);
+        bottom.push(borders.vertical);
+        lines.push(top);
+        lines.push(bottom);
+        lines.push(rule(r + 1));
+    }
+    lines
+}
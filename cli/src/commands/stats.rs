@@ -0,0 +1,274 @@
+//! Summary statistics over a directory of `.puz` files.
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use comfy_table::{Cell, CellAlignment, Table};
+use puz_parse::Puzzle;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::commands::to_stdout;
+use crate::input;
+use crate::render;
+
+#[derive(Args)]
+pub(crate) struct StatsArgs {
+    /// directory to scan recursively for .puz files and archives
+    #[arg(value_name = "DIR")]
+    dir: PathBuf,
+
+    /// how many answers and authors to list (0 for all)
+    #[arg(long, value_name = "N", default_value_t = 20)]
+    top: usize,
+
+    /// output format
+    #[arg(long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+}
+
+#[derive(Serialize)]
+struct Stats {
+    files: usize,
+    parsed: usize,
+    parse_errors: usize,
+    /// Mean share of squares that are black, across parsed puzzles.
+    black_square_density: f64,
+    /// Mean number of entries (across + down) per puzzle.
+    average_word_count: f64,
+    with_rebus: Share,
+    with_circles: Share,
+    diagramless: Share,
+    /// `"15x15"` etc., most common first.
+    grid_sizes: Vec<Count>,
+    /// Answer length in squares, shortest first.
+    word_lengths: Vec<Count>,
+    top_answers: Vec<Count>,
+    authors: Vec<Count>,
+    /// Warning counts by [`PuzWarning::kind`](puz_parse::PuzWarning::kind).
+    warnings: Vec<Count>,
+}
+
+#[derive(Serialize)]
+struct Share {
+    count: usize,
+    percent: f64,
+}
+
+#[derive(Serialize)]
+struct Count {
+    value: String,
+    count: usize,
+}
+
+/// Running totals while scanning.
+#[derive(Default)]
+struct Tally {
+    parsed: usize,
+    parse_errors: usize,
+    density_sum: f64,
+    words: usize,
+    rebus: usize,
+    circles: usize,
+    diagramless: usize,
+    grid_sizes: HashMap<(u8, u8), usize>,
+    word_lengths: BTreeMap<usize, usize>,
+    answers: HashMap<String, usize>,
+    authors: HashMap<String, usize>,
+    warnings: HashMap<&'static str, usize>,
+}
+
+impl Tally {
+    fn add(&mut self, puzzle: &Puzzle) {
+        self.parsed += 1;
+        let info = &puzzle.info;
+        *self
+            .grid_sizes
+            .entry((info.width, info.height))
+            .or_default() += 1;
+        *self
+            .authors
+            .entry(info.author.trim().to_string())
+            .or_default() += 1;
+
+        let squares = usize::from(info.width) * usize::from(info.height);
+        // Black squares come from the blank grid: a '.' in the solution can
+        // also be a literal period inside an answer.
+        let black: usize = puzzle
+            .grid
+            .blank
+            .iter()
+            .map(|row| row.chars().filter(|&c| c == '.').count())
+            .sum();
+        if squares > 0 {
+            self.density_sum += black as f64 / squares as f64;
+        }
+
+        let entries = puzzle.clue_answers();
+        self.words += entries.len();
+        for entry in entries {
+            *self
+                .word_lengths
+                .entry(entry.answer.chars().count())
+                .or_default() += 1;
+            *self.answers.entry(entry.answer).or_default() += 1;
+        }
+
+        let extensions = &puzzle.extensions;
+        self.rebus += usize::from(extensions.rebus.is_some());
+        self.circles += usize::from(
+            extensions
+                .circles
+                .as_ref()
+                .is_some_and(|g| g.iter().flatten().any(|&c| c)),
+        );
+        self.diagramless += usize::from(info.is_diagramless);
+    }
+
+    fn finish(self, files: usize, top: usize) -> Stats {
+        let parsed = self.parsed;
+        let mean = |sum: f64| {
+            if parsed == 0 {
+                0.0
+            } else {
+                sum / parsed as f64
+            }
+        };
+        let share = |count: usize| Share {
+            count,
+            percent: 100.0 * mean(count as f64),
+        };
+        let limit = if top == 0 { usize::MAX } else { top };
+
+        Stats {
+            files,
+            parsed,
+            parse_errors: self.parse_errors,
+            black_square_density: mean(self.density_sum),
+            average_word_count: mean(self.words as f64),
+            with_rebus: share(self.rebus),
+            with_circles: share(self.circles),
+            diagramless: share(self.diagramless),
+            grid_sizes: ranked(
+                self.grid_sizes
+                    .into_iter()
+                    .map(|((w, h), n)| (format!("{w}x{h}"), n)),
+                usize::MAX,
+            ),
+            word_lengths: self
+                .word_lengths
+                .into_iter()
+                .map(|(len, count)| Count {
+                    value: len.to_string(),
+                    count,
+                })
+                .collect(),
+            top_answers: ranked(self.answers, limit),
+            authors: ranked(self.authors, limit),
+            warnings: ranked(
+                self.warnings.into_iter().map(|(k, n)| (k.to_string(), n)),
+                usize::MAX,
+            ),
+        }
+    }
+}
+
+/// Most common first, ties broken alphabetically so output is stable.
+fn ranked(counts: impl IntoIterator<Item = (String, usize)>, limit: usize) -> Vec<Count> {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+        .into_iter()
+        .take(limit)
+        .map(|(value, count)| Count { value, count })
+        .collect()
+}
+
+pub(crate) fn run(args: StatsArgs) -> Result<()> {
+    let files = input::find(&args.dir)?;
+
+    let mut tally = Tally::default();
+    let mut scanned = 0usize;
+    for input in input::expand(files) {
+        scanned += 1;
+        let Ok(data) = input.read() else {
+            tally.parse_errors += 1;
+            continue;
+        };
+        match Puzzle::reader().from_bytes_verbose(&data) {
+            Ok(parsed) => {
+                for warning in &parsed.warnings {
+                    *tally.warnings.entry(warning.kind()).or_default() += 1;
+                }
+                tally.add(&parsed.result);
+            }
+            Err(_) => tally.parse_errors += 1,
+        }
+    }
+    let stats = tally.finish(scanned, args.top);
+
+    to_stdout(|out| match args.format {
+        Format::Json => Ok(writeln!(out, "{}", serde_json::to_string_pretty(&stats)?)?),
+        Format::Table => print_tables(out, &stats),
+    })
+}
+
+fn print_tables(out: &mut dyn Write, stats: &Stats) -> Result<()> {
+    let mut summary = render::borderless_table();
+    let share = |s: &Share| format!("{} ({:.1}%)", s.count, s.percent);
+    summary
+        .add_row(vec!["files", &stats.files.to_string()])
+        .add_row(vec!["parsed", &stats.parsed.to_string()])
+        .add_row(vec!["parse errors", &stats.parse_errors.to_string()])
+        .add_row(vec![
+            "black-square density",
+            &format!("{:.1}%", 100.0 * stats.black_square_density),
+        ])
+        .add_row(vec![
+            "average word count",
+            &format!("{:.1}", stats.average_word_count),
+        ])
+        .add_row(vec!["with rebus", &share(&stats.with_rebus)])
+        .add_row(vec!["with circles", &share(&stats.with_circles)])
+        .add_row(vec!["diagramless", &share(&stats.diagramless)]);
+    writeln!(out, "{summary}")?;
+
+    for (heading, counts) in [
+        ("Grid sizes", &stats.grid_sizes),
+        ("Word lengths", &stats.word_lengths),
+        ("Top answers", &stats.top_answers),
+        ("Authors", &stats.authors),
+        ("Warnings", &stats.warnings),
+    ] {
+        if counts.is_empty() {
+            continue;
+        }
+        writeln!(out)?;
+        writeln!(out, "{}", render::bold(heading))?;
+        writeln!(out, "{}", count_table(counts))?;
+    }
+    Ok(())
+}
+
+fn count_table(counts: &[Count]) -> Table {
+    let mut table = render::borderless_table();
+    for c in counts {
+        let value = if c.value.is_empty() {
+            render::dim("(none)")
+        } else {
+            c.value.clone()
+        };
+        table.add_row(vec![
+            Cell::new(value),
+            Cell::new(c.count).set_alignment(CellAlignment::Right),
+        ]);
+    }
+    table
+}
@@ -0,0 +1,269 @@
+//! Export a puzzle as a Markdown or plain-text document.
+//!
+//! Unlike `show`, the output is meant to be saved and embedded (in a wiki page,
+//! a chat message, a README), so it never contains terminal styling: Markdown
+//! uses a table for the grid, and plain text uses the ASCII grid from `show`.
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use puz_parse::{ClueAnswer, Direction, Puzzle};
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use crate::commands::show::{self, Mode};
+use crate::commands::{answer_cells, circled, to_stdout};
+use crate::input;
+
+#[derive(Args)]
+pub(crate) struct TextArgs {
+    /// the .puz file to export (- for stdin)
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+
+    /// write output to a file instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// output format
+    #[arg(long, value_enum, default_value_t = Format::Markdown)]
+    format: Format,
+
+    /// include the answers (in a collapsed spoiler block for Markdown)
+    #[arg(long)]
+    answers: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Markdown,
+    Plain,
+}
+
+pub(crate) fn run(args: TextArgs) -> Result<()> {
+    let data = input::read(&args.file)?;
+    let parsed = Puzzle::reader()
+        .from_bytes_verbose(&data)
+        .with_context(|| format!("failed to parse {}", args.file.display()))?;
+    for warning in &parsed.warnings {
+        eprintln!("Warning in {}: {warning}", args.file.display());
+    }
+    let puzzle = parsed.result;
+
+    let text = match args.format {
+        Format::Markdown => markdown(&puzzle, args.answers),
+        Format::Plain => plain(&puzzle, args.answers),
+    };
+    match &args.output {
+        Some(path) => input::write(path, text)?,
+        None => to_stdout(|out| Ok(out.write_all(text.as_bytes())?))?,
+    }
+    Ok(())
+}
+
+const DIRECTIONS: [(&str, Direction); 2] =
+    [("Across", Direction::Across), ("Down", Direction::Down)];
+
+// `write!` into a `String` cannot fail, so its results are ignored below.
+
+/// Clues are a bullet list with the number in bold, not an ordered list:
+/// Markdown renderers renumber `1.`, `5.`, `9.` as 1, 2, 3.
+fn markdown(puzzle: &Puzzle, answers: bool) -> String {
+    let info = &puzzle.info;
+    let mut out = String::new();
+    let title = if info.title.is_empty() {
+        "Crossword"
+    } else {
+        &info.title
+    };
+    let _ = writeln!(out, "# {}\n", escape(title));
+
+    out.push_str("| | |\n| --- | --- |\n");
+    for (label, value) in [
+        ("Author", info.author.as_str()),
+        ("Copyright", info.copyright.as_str()),
+        ("Size", &format!("{} \u{00D7} {}", info.width, info.height)),
+        ("Notes", info.notes.as_str()),
+    ] {
+        if !value.is_empty() {
+            // A line break would end the table row.
+            let _ = writeln!(out, "| {label} | {} |", escape(&value.replace('\n', " ")));
+        }
+    }
+
+    out.push_str("\n## Grid\n\n");
+    markdown_grid(&mut out, puzzle);
+
+    let entries = puzzle.clue_answers();
+    for (heading, direction) in DIRECTIONS {
+        let _ = writeln!(out, "\n## {heading}\n");
+        for entry in entries.iter().filter(|e| e.direction == direction) {
+            let _ = writeln!(out, "- **{}** {}", entry.number, escape(&entry.clue));
+        }
+    }
+
+    if answers {
+        out.push_str("\n<details>\n<summary>Answers</summary>\n\n```text\n");
+        out.push_str(&show::grid_lines(puzzle, Mode::Solution, false).join("\n"));
+        out.push_str("\n```\n");
+        for (heading, direction) in DIRECTIONS {
+            let _ = writeln!(out, "\n**{heading}**\n");
+            for entry in entries.iter().filter(|e| e.direction == direction) {
+                let _ = writeln!(
+                    out,
+                    "- **{}** {}",
+                    entry.number,
+                    escape(&answer(puzzle, entry))
+                );
+            }
+        }
+        out.push_str("\n</details>\n");
+    }
+    out
+}
+
+/// The grid as a Markdown table: `■` for black squares, clue numbers in
+/// numbered squares, and circled squares marked `◯` (after the number, if
+/// any). Markdown tables need a header row, so the column numbers serve as one.
+fn markdown_grid(out: &mut String, puzzle: &Puzzle) {
+    let width = puzzle.info.width as usize;
+    let mut numbers = vec![vec![None; width]; puzzle.info.height as usize];
+    for entry in puzzle.clue_answers() {
+        numbers[entry.row][entry.col] = Some(entry.number);
+    }
+
+    out.push('|');
+    for c in 1..=width {
+        let _ = write!(out, " {c} |");
+    }
+    out.push_str("\n|");
+    out.push_str(&" :-: |".repeat(width));
+    out.push('\n');
+
+    for (r, row) in puzzle.grid.blank.iter().enumerate() {
+        out.push('|');
+        for (c, ch) in row.chars().enumerate() {
+            let mut cell = String::new();
+            if ch == '.' {
+                cell.push('\u{25A0}');
+            } else {
+                if let Some(n) = numbers[r][c] {
+                    cell.push_str(&n.to_string());
+                }
+                if circled(puzzle, r, c) {
+                    cell.push('\u{25EF}');
+                }
+            }
+            let _ = write!(out, " {cell} |");
+        }
+        out.push('\n');
+    }
+}
+
+fn plain(puzzle: &Puzzle, answers: bool) -> String {
+    let info = &puzzle.info;
+    let mut out = String::new();
+    for line in [&info.title, &info.author, &info.copyright] {
+        if !line.is_empty() {
+            let _ = writeln!(out, "{line}");
+        }
+    }
+    let _ = writeln!(out, "{} x {}\n", info.width, info.height);
+    out.push_str(&show::grid_lines(puzzle, Mode::Empty, false).join("\n"));
+    out.push('\n');
+    if !info.notes.is_empty() {
+        let _ = writeln!(out, "\n{}", info.notes);
+    }
+
+    let entries = puzzle.clue_answers();
+    for (heading, direction) in DIRECTIONS {
+        let _ = writeln!(out, "\n{}\n", heading.to_uppercase());
+        for entry in entries.iter().filter(|e| e.direction == direction) {
+            let _ = writeln!(out, "{:>3}. {}", entry.number, entry.clue);
+        }
+    }
+
+    if answers {
+        out.push_str("\nANSWERS\n\n");
+        out.push_str(&show::grid_lines(puzzle, Mode::Solution, false).join("\n"));
+        out.push('\n');
+        for (heading, direction) in DIRECTIONS {
+            let _ = writeln!(out, "\n{heading}:");
+            for entry in entries.iter().filter(|e| e.direction == direction) {
+                let _ = writeln!(out, "{:>3}. {}", entry.number, answer(puzzle, entry));
+            }
+        }
+    }
+    out
+}
+
+/// An entry's answer with rebus squares spelled out in full, bracketed so the
+/// square boundaries stay visible (e.g. `C[HEART]S`).
+fn answer(puzzle: &Puzzle, entry: &ClueAnswer) -> String {
+    answer_cells(puzzle, entry)
+        .into_iter()
+        .map(|cell| {
+            if cell.chars().count() > 1 {
+                format!("[{cell}]")
+            } else {
+                cell
+            }
+        })
+        .collect()
+}
+
+/// Escape characters Markdown would otherwise treat as formatting (or, for
+/// `|`, as a table column break).
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        if matches!(
+            ch,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#'
+        ) {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Across entries 1, 3, and 5; down entries 1, 2, and 4.
+    fn sample() -> Puzzle {
+        let mut p = Puzzle::new().title("T").grid(["AB.", "CDE", ".FG"]);
+        for (n, clue) in [(1, "one"), (3, "three"), (5, "five")] {
+            p.clues.across.set(n, clue);
+        }
+        for (n, clue) in [(1, "one down"), (2, "two down"), (4, "four down")] {
+            p.clues.down.set(n, clue);
+        }
+        p
+    }
+
+    #[test]
+    fn test_markdown_keeps_clue_numbers() {
+        let md = markdown(&sample(), false);
+        assert!(md.contains("- **1** one\n- **3** three\n- **5** five\n"));
+        assert!(md.contains("- **4** four down\n"));
+        // Nothing a renderer would treat as an ordered list item.
+        assert!(
+            !md.lines()
+                .any(|l| l.starts_with(|c: char| c.is_ascii_digit()))
+        );
+    }
+
+    #[test]
+    fn test_markdown_answers_keep_numbers() {
+        let md = markdown(&sample(), true);
+        assert!(md.contains("- **5** FG\n"));
+        assert!(md.contains("- **4** EG\n"));
+    }
+
+    #[test]
+    fn test_escape_markdown_syntax() {
+        assert_eq!(escape("a|b *c* #1"), "a\\|b \\*c\\* \\#1");
+    }
+}
@@ -1,85 +0,0 @@
-//! Bulk-validate a directory of `.puz` files.
-
-use anyhow::Result;
-use clap::Args;
-use puz_parse::{PuzWarning, Puzzle};
-use std::path::PathBuf;
-
-use crate::commands::collect_puz_files;
-
-#[derive(Args)]
-pub(crate) struct ValidateArgs {
-    /// directory to scan recursively for .puz files
-    #[arg(value_name = "DIR")]
-    dir: PathBuf,
-
-    /// print a line for every file, including clean ones
-    #[arg(long)]
-    verbose: bool,
-
-    /// print only parse failures, not warnings
-    #[arg(long)]
-    errors_only: bool,
-}
-
-pub(crate) fn run(args: ValidateArgs) -> Result<()> {
-    let mut files = collect_puz_files(&args.dir);
-    files.sort();
-
-    if files.is_empty() {
-        anyhow::bail!("no .puz files found under {}", args.dir.display());
-    }
-
-    let mut parse_errors = 0usize;
-    let mut files_with_warnings = 0usize;
-    let mut checksum_mismatches = 0usize;
-    let mut other_warnings = 0usize;
-
-    for path in &files {
-        let data = match std::fs::read(path) {
-            Ok(d) => d,
-            Err(e) => {
-                parse_errors += 1;
-                println!("READ-ERR {}: {e}", path.display());
-                continue;
-            }
-        };
-
-        match Puzzle::reader().from_bytes_verbose(&data) {
-            Ok(parsed) => {
-                if parsed.warnings.is_empty() {
-                    if args.verbose {
-                        println!("OK       {}", path.display());
-                    }
-                } else {
-                    files_with_warnings += 1;
-                    for w in &parsed.warnings {
-                        match w {
-                            PuzWarning::ChecksumMismatch { .. } => checksum_mismatches += 1,
-                            _ => other_warnings += 1,
-                        }
-                        if !args.errors_only {
-                            println!("WARN     {}: {w}", path.display());
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                parse_errors += 1;
-                println!("PARSE-ERR {}: {e}", path.display());
-            }
-        }
-    }
-
-    let total = files.len();
-    let clean = total - parse_errors - files_with_warnings;
-    println!("\n=== summary ===");
-    println!("scanned:            {total}");
-    println!("parse errors:       {parse_errors}");
-    println!("files w/ warnings:  {files_with_warnings}");
-    println!("  checksum mismatch:  {checksum_mismatches}");
-    println!("  other warnings:     {other_warnings}");
-    println!("clean:              {clean}");
-
-    Ok(())
-}
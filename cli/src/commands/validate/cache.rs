@@ -0,0 +1,202 @@
+//! The `--cache` file: each file's size, modification time, content hash, and
+//! [`Outcome`] from the last run, so unchanged files aren't parsed again.
+//!
+//! A file whose size and modification time match its entry is reused without
+//! being read. Otherwise it is read and hashed, and reused only if the hash
+//! still matches (e.g. after a `touch` or a fresh checkout).
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use super::Outcome;
+use crate::commands::sha256_hex;
+use crate::input::Input;
+
+/// The cache layout and the meaning of its outcomes. Bump this whenever the
+/// [`Outcome`] format or what a parse reports changes, so caches written by
+/// development builds between releases aren't reused either.
+const SCHEMA: u32 = 1;
+
+/// The `puz` version and [`SCHEMA`] that wrote a cache, e.g. `0.1.3+1`.
+fn version() -> String {
+    format!("{}+{SCHEMA}", env!("CARGO_PKG_VERSION"))
+}
+
+#[derive(Serialize, Deserialize)]
+pub(super) struct Cache {
+    /// The [`version`] that wrote the cache. Another version may parse
+    /// differently, so its entries aren't reused.
+    version: String,
+    /// Keyed by path, as shown in the report, and sorted so the file diffs
+    /// cleanly between runs.
+    files: BTreeMap<String, Entry>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(super) struct Entry {
+    size: u64,
+    /// Nanoseconds since the Unix epoch, if the platform reports it.
+    mtime_ns: Option<u64>,
+    sha256: String,
+    outcome: Outcome,
+}
+
+/// One file's outcome, and the entry to store for it in the next cache.
+pub(super) struct Checked {
+    pub(super) outcome: Outcome,
+    /// `None` for a file that couldn't be read, so it is retried next time.
+    pub(super) entry: Option<Entry>,
+    /// Whether the outcome came from the cache rather than a parse.
+    pub(super) reused: bool,
+}
+
+impl Cache {
+    pub(super) fn new() -> Self {
+        Cache {
+            version: version(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// The cache at `path`, or an empty one if there is none yet. A cache
+    /// that can't be read is reported and ignored; it is rebuilt by this run.
+    pub(super) fn load(path: &Path) -> Self {
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Cache::new(),
+            Err(e) => {
+                eprintln!("ignoring cache {}: {e}", path.display());
+                return Cache::new();
+            }
+        };
+        match serde_json::from_slice::<Cache>(&data) {
+            Ok(cache) if cache.version == version() => cache,
+            Ok(_) => Cache::new(),
+            Err(e) => {
+                eprintln!("ignoring cache {}: {e}", path.display());
+                Cache::new()
+            }
+        }
+    }
+
+    pub(super) fn insert(&mut self, input: &Input, entry: Entry) {
+        self.files.insert(input.name().into_owned(), entry);
+    }
+
+    /// Write the cache through a temporary file, so an interrupted run
+    /// leaves the previous cache intact.
+    pub(super) fn save(&self, path: &Path) -> Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let mut out = BufWriter::new(File::create(&tmp)?);
+        serde_json::to_writer(&mut out, self)?;
+        out.flush()?;
+        drop(out);
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Validate `input`, reusing its cached outcome if the file hasn't
+    /// changed.
+    pub(super) fn check(&self, input: &Input) -> Checked {
+        let unreadable = |e: std::io::Error| Checked {
+            outcome: Outcome::Unreadable(e.to_string()),
+            entry: None,
+            reused: false,
+        };
+        let previous = self.files.get(input.name().as_ref());
+
+        // Archive members have no size or time of their own to check, so
+        // they are always hashed.
+        let (size, mtime_ns) = match input.path().map(fs::metadata) {
+            Some(Ok(meta)) => (meta.len(), modified_ns(&meta)),
+            Some(Err(e)) => return unreadable(e),
+            None => (0, None),
+        };
+        if let Some(entry) = previous
+            && mtime_ns.is_some()
+            && entry.size == size
+            && entry.mtime_ns == mtime_ns
+        {
+            return Checked {
+                outcome: entry.outcome.clone(),
+                entry: Some(entry.clone()),
+                reused: true,
+            };
+        }
+
+        let data = match input.read() {
+            Ok(data) => data,
+            Err(e) => return unreadable(e),
+        };
+        let sha256 = sha256_hex(&data);
+        let (outcome, reused) = match previous {
+            Some(entry) if entry.sha256 == sha256 => (entry.outcome.clone(), true),
+            _ => (Outcome::parse(&data), false),
+        };
+        Checked {
+            outcome: outcome.clone(),
+            entry: Some(Entry {
+                size: data.len() as u64,
+                mtime_ns,
+                sha256,
+                outcome,
+            }),
+            reused,
+        }
+    }
+}
+
+fn modified_ns(meta: &fs::Metadata) -> Option<u64> {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .and_then(|d| u64::try_from(d.as_nanos()).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use puz_parse::Puzzle;
+
+    #[test]
+    fn test_version_includes_schema() {
+        assert_eq!(version(), format!("{}+{SCHEMA}", env!("CARGO_PKG_VERSION")));
+    }
+
+    #[test]
+    fn test_load_reuses_only_its_own_version() {
+        let dir = std::env::temp_dir().join(format!("puz-cache-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let puz = dir.join("a.puz");
+        let puzzle = Puzzle::new().title("T").grid(["AB", "CD"]);
+        fs::write(&puz, puz_parse::to_bytes(&puzzle).unwrap()).unwrap();
+        let input = Input::File(puz);
+        let cache_path = dir.join("cache.json");
+
+        let mut cache = Cache::new();
+        let checked = cache.check(&input);
+        assert!(!checked.reused);
+        cache.insert(&input, checked.entry.unwrap());
+        cache.save(&cache_path).unwrap();
+        let reused = Cache::load(&cache_path).check(&input).reused;
+
+        // The same cache as written by another version, or by this one
+        // before a schema change.
+        let text = fs::read_to_string(&cache_path).unwrap();
+        let old = text.replace(&version(), &format!("{}+0", env!("CARGO_PKG_VERSION")));
+        assert_ne!(old, text);
+        fs::write(&cache_path, old).unwrap();
+        let stale = Cache::load(&cache_path);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(reused);
+        assert!(stale.files.is_empty());
+        assert_eq!(stale.version, version());
+    }
+}
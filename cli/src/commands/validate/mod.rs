@@ -0,0 +1,390 @@
+//! Bulk-validate a directory of `.puz` files.
+//!
+//! Results print as text by default, or in one of the machine-readable formats
+//! in [`report`] for CI.
+
+mod cache;
+mod report;
+
+use anyhow::{Context, Result};
+use clap::builder::PossibleValuesParser;
+use clap::{Args, ValueEnum};
+use puz_parse::{PuzWarning, Puzzle};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+
+use crate::commands::{default_jobs, parallel_in_order, to_stdout};
+use crate::input::{self, Input};
+use cache::{Cache, Checked};
+
+#[derive(Args)]
+pub(crate) struct ValidateArgs {
+    /// directory to scan recursively for .puz files and archives
+    #[arg(value_name = "DIR")]
+    dir: PathBuf,
+
+    /// print a line for every file, including clean ones (text format)
+    #[arg(long)]
+    verbose: bool,
+
+    /// print only parse failures, not warnings (text format)
+    #[arg(long)]
+    errors_only: bool,
+
+    /// output format
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// exit with status 1 if any file has a problem at this level
+    #[arg(long, value_enum, value_name = "LEVEL")]
+    fail_on: Option<FailOn>,
+
+    /// ignore warnings of this kind (e.g. ChecksumMismatch); repeatable
+    #[arg(
+        long,
+        value_name = "KIND",
+        value_delimiter = ',',
+        value_parser = PossibleValuesParser::new(PuzWarning::KINDS)
+    )]
+    allow: Vec<String>,
+
+    /// treat warnings of this kind as errors (e.g. UnbackedGridChar); repeatable
+    #[arg(
+        long,
+        value_name = "KIND",
+        value_delimiter = ',',
+        value_parser = PossibleValuesParser::new(PuzWarning::KINDS)
+    )]
+    deny: Vec<String>,
+
+    /// reuse results for unchanged files from this cache, and update it
+    #[arg(long, value_name = "FILE")]
+    cache: Option<PathBuf>,
+
+    /// re-parse every file, ignoring (but still rewriting) the --cache
+    #[arg(long, requires = "cache")]
+    no_cache: bool,
+
+    /// files to parse in parallel (default: the number of CPUs)
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<NonZeroUsize>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// a line per problem, then a summary
+    Text,
+    /// one JSON document with every file and the summary
+    Json,
+    /// one JSON object per file
+    Jsonl,
+    /// a JUnit XML report with a test case per file
+    Junit,
+    /// a SARIF 2.1.0 log with a result per problem
+    Sarif,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FailOn {
+    /// parse errors or warnings
+    Warnings,
+    /// parse errors only
+    Errors,
+}
+
+/// The `kind` of a file that couldn't be read at all, as opposed to one that
+/// failed to parse (whose kind is a [`PuzError::kind`](puz_parse::PuzError::kind)).
+const READ_ERROR: &str = "ReadError";
+
+/// What parsing one file found, before `--allow` and `--deny` apply, so a
+/// cached outcome holds under any policy.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Outcome {
+    /// The file couldn't be read.
+    Unreadable(String),
+    /// Parsing failed with this error.
+    Failed(Problem),
+    /// Parsing succeeded with these warnings.
+    Parsed(Vec<Problem>),
+}
+
+impl Outcome {
+    fn of_input(input: &Input) -> Self {
+        match input.read() {
+            Ok(data) => Outcome::parse(&data),
+            Err(e) => Outcome::Unreadable(e.to_string()),
+        }
+    }
+
+    fn parse(data: &[u8]) -> Self {
+        // Only the warnings are kept from each parse, not the puzzles.
+        match Puzzle::reader().from_bytes_verbose(data) {
+            Ok(parsed) => Outcome::Parsed(
+                parsed
+                    .warnings
+                    .iter()
+                    .map(|w| Problem::new(w.kind(), w.to_string()))
+                    .collect(),
+            ),
+            Err(e) => Outcome::Failed(Problem::new(e.kind(), e.to_string())),
+        }
+    }
+}
+
+/// The outcome of validating one file.
+#[derive(Serialize)]
+struct FileReport {
+    file: String,
+    status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<Problem>,
+    warnings: Vec<Problem>,
+    /// Kinds of the warnings dropped by `--allow`, for the summary counts.
+    #[serde(skip)]
+    allowed: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// A parse error or warning: the variant name and its message.
+#[derive(Clone, Serialize, Deserialize)]
+struct Problem {
+    kind: String,
+    message: String,
+    /// A warning escalated to an error by `--deny`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    denied: bool,
+}
+
+impl Problem {
+    fn new(kind: &str, message: String) -> Self {
+        Problem {
+            kind: kind.to_string(),
+            message,
+            denied: false,
+        }
+    }
+}
+
+impl FileReport {
+    fn new(input: &Input, outcome: Outcome, args: &ValidateArgs) -> Self {
+        let mut allowed = Vec::new();
+        let (error, warnings) = match outcome {
+            Outcome::Unreadable(message) => (Some(Problem::new(READ_ERROR, message)), Vec::new()),
+            Outcome::Failed(error) => (Some(error), Vec::new()),
+            Outcome::Parsed(warnings) => {
+                let mut kept = Vec::new();
+                for mut w in warnings {
+                    if args.allow.contains(&w.kind) {
+                        allowed.push(w.kind);
+                    } else {
+                        w.denied = args.deny.contains(&w.kind);
+                        kept.push(w);
+                    }
+                }
+                (None, kept)
+            }
+        };
+        let status = if error.is_some() || warnings.iter().any(|w| w.denied) {
+            Status::Error
+        } else if warnings.is_empty() {
+            Status::Ok
+        } else {
+            Status::Warning
+        };
+        FileReport {
+            file: input.name().into_owned(),
+            status,
+            error,
+            warnings,
+            allowed,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Summary {
+    scanned: usize,
+    parse_errors: usize,
+    /// Files that parsed but have a `--deny`'d warning.
+    files_denied: usize,
+    files_with_warnings: usize,
+    clean: usize,
+    /// Every warning found, allowed and denied ones included, for each
+    /// [`PuzWarning::KINDS`] entry in order.
+    #[serde(serialize_with = "as_map")]
+    warnings: Vec<(&'static str, usize)>,
+}
+
+fn as_map<S: serde::Serializer>(
+    counts: &[(&'static str, usize)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(counts.iter().copied())
+}
+
+impl Summary {
+    fn new() -> Self {
+        Summary {
+            scanned: 0,
+            parse_errors: 0,
+            files_denied: 0,
+            files_with_warnings: 0,
+            clean: 0,
+            warnings: PuzWarning::KINDS.iter().map(|&kind| (kind, 0)).collect(),
+        }
+    }
+
+    fn add(&mut self, report: &FileReport) {
+        self.scanned += 1;
+        match report.status {
+            Status::Ok => self.clean += 1,
+            Status::Warning => self.files_with_warnings += 1,
+            Status::Error if report.error.is_some() => self.parse_errors += 1,
+            Status::Error => self.files_denied += 1,
+        }
+        let kinds = report.warnings.iter().map(|w| &w.kind);
+        for kind in kinds.chain(&report.allowed) {
+            if let Some((_, count)) = self.warnings.iter_mut().find(|(k, _)| *k == kind) {
+                *count += 1;
+            }
+        }
+    }
+}
+
+pub(crate) fn run(args: ValidateArgs) -> Result<()> {
+    let files = input::find(&args.dir)?;
+    if let Some(kind) = args.allow.iter().find(|k| args.deny.contains(k)) {
+        anyhow::bail!("{kind} is given to both --allow and --deny");
+    }
+
+    let jobs = args.jobs.map_or_else(default_jobs, NonZeroUsize::get);
+    let mut summary = Summary::new();
+    // Reports for the formats written as one document at the end; text and
+    // JSON Lines print as they go.
+    let mut reports = Vec::new();
+
+    // With --no-cache, every file is parsed, but the cache is still rewritten.
+    let previous = args.cache.as_ref().map(|path| {
+        if args.no_cache {
+            Cache::new()
+        } else {
+            Cache::load(path)
+        }
+    });
+    let mut fresh = Cache::new();
+    let mut reused = 0usize;
+
+    let check = |input: &Input| match &previous {
+        Some(cache) => cache.check(input),
+        None => Checked {
+            outcome: Outcome::of_input(input),
+            entry: None,
+            reused: false,
+        },
+    };
+    to_stdout(|out| {
+        parallel_in_order(input::expand(files), jobs, check, |input, checked| {
+            reused += usize::from(checked.reused);
+            if let Some(entry) = checked.entry {
+                fresh.insert(input, entry);
+            }
+            let report = FileReport::new(input, checked.outcome, &args);
+            summary.add(&report);
+            match args.format {
+                Format::Text => print_text(out, &report, &args)?,
+                Format::Jsonl => writeln!(out, "{}", serde_json::to_string(&report)?)?,
+                Format::Json | Format::Junit | Format::Sarif => reports.push(report),
+            }
+            Ok(())
+        })?;
+
+        if let Some(path) = &args.cache {
+            fresh
+                .save(path)
+                .with_context(|| format!("failed to write cache {}", path.display()))?;
+            eprintln!(
+                "{reused} of {} files unchanged since the cached run",
+                summary.scanned
+            );
+        }
+
+        match args.format {
+            Format::Text => print_summary(out, &summary, &args)?,
+            Format::Jsonl => {}
+            Format::Json => writeln!(out, "{}", report::json(&reports, &summary)?)?,
+            Format::Junit => write!(out, "{}", report::junit(&args.dir, &reports, &summary))?,
+            Format::Sarif => writeln!(out, "{}", report::sarif(&reports)?)?,
+        }
+        Ok(())
+    })?;
+
+    let failed = match args.fail_on {
+        Some(FailOn::Warnings) => {
+            summary.parse_errors + summary.files_denied + summary.files_with_warnings > 0
+        }
+        Some(FailOn::Errors) => summary.parse_errors + summary.files_denied > 0,
+        None => false,
+    };
+    if failed {
+        std::io::stdout().flush()?;
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn print_text(out: &mut dyn Write, report: &FileReport, args: &ValidateArgs) -> Result<()> {
+    let file = &report.file;
+    match &report.error {
+        Some(e) if e.kind == READ_ERROR => writeln!(out, "READ-ERR {file}: {}", e.message)?,
+        Some(e) => writeln!(out, "PARSE-ERR {file}: {}", e.message)?,
+        None if report.warnings.is_empty() => {
+            if args.verbose {
+                writeln!(out, "OK       {file}")?;
+            }
+        }
+        None => {
+            for w in &report.warnings {
+                if w.denied {
+                    writeln!(out, "DENIED   {file}: {}", w.message)?;
+                } else if !args.errors_only {
+                    writeln!(out, "WARN     {file}: {}", w.message)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_summary(out: &mut dyn Write, summary: &Summary, args: &ValidateArgs) -> Result<()> {
+    writeln!(out, "\n=== summary ===")?;
+    writeln!(out, "scanned:            {}", summary.scanned)?;
+    writeln!(out, "parse errors:       {}", summary.parse_errors)?;
+    if !args.deny.is_empty() {
+        writeln!(out, "files w/ denied:    {}", summary.files_denied)?;
+    }
+    writeln!(out, "files w/ warnings:  {}", summary.files_with_warnings)?;
+    writeln!(out, "clean:              {}", summary.clean)?;
+    writeln!(out, "\nwarnings by kind:")?;
+    let width = PuzWarning::KINDS.iter().map(|k| k.len()).max().unwrap_or(0) + 2;
+    for (kind, count) in &summary.warnings {
+        let note = if args.allow.iter().any(|a| a == kind) {
+            " (allowed)"
+        } else if args.deny.iter().any(|d| d == kind) {
+            " (denied)"
+        } else {
+            ""
+        };
+        writeln!(out, "  {kind:<width$}{count}{note}")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,210 @@
+//! Machine-readable validation reports: a JSON document, JUnit XML, and SARIF.
+//!
+//! JSON Lines needs nothing beyond serializing each [`FileReport`], so it is
+//! written directly by the caller.
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::json;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use super::{FileReport, Status, Summary};
+
+/// Every file's report followed by the summary counts.
+pub(super) fn json(reports: &[FileReport], summary: &Summary) -> Result<String> {
+    #[derive(Serialize)]
+    struct Document<'a> {
+        files: &'a [FileReport],
+        summary: &'a Summary,
+    }
+    Ok(serde_json::to_string_pretty(&Document {
+        files: reports,
+        summary,
+    })?)
+}
+
+/// A JUnit report with one test case per file. A parse error is an `<error>`;
+/// warnings are one `<failure>` listing each of them, or an `<error>` if any
+/// was denied.
+pub(super) fn junit(dir: &Path, reports: &[FileReport], summary: &Summary) -> String {
+    let mut out = String::new();
+    let counts = format!(
+        r#"tests="{}" failures="{}" errors="{}""#,
+        summary.scanned,
+        summary.files_with_warnings,
+        summary.parse_errors + summary.files_denied
+    );
+    let _ = writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(out, r#"<testsuites name="puz validate" {counts}>"#);
+    let _ = writeln!(
+        out,
+        r#"  <testsuite name="{}" {counts}>"#,
+        escape(&dir.display().to_string())
+    );
+    for report in reports {
+        let name = escape(&report.file);
+        if report.status == Status::Ok {
+            let _ = writeln!(
+                out,
+                r#"    <testcase classname="puz.validate" name="{name}"/>"#
+            );
+            continue;
+        }
+        let _ = writeln!(
+            out,
+            r#"    <testcase classname="puz.validate" name="{name}">"#
+        );
+        if let Some(e) = &report.error {
+            let _ = writeln!(
+                out,
+                r#"      <error type="{}" message="{}"/>"#,
+                e.kind,
+                escape(&e.message)
+            );
+        } else {
+            // Warnings escalated by --deny make the whole case an error.
+            let element = if report.status == Status::Error {
+                "error"
+            } else {
+                "failure"
+            };
+            let mut kinds: Vec<&str> = Vec::new();
+            for w in &report.warnings {
+                if !kinds.contains(&w.kind.as_str()) {
+                    kinds.push(&w.kind);
+                }
+            }
+            let details: Vec<String> = report
+                .warnings
+                .iter()
+                .map(|w| escape(&format!("{}: {}", w.kind, w.message)))
+                .collect();
+            let _ = writeln!(
+                out,
+                r#"      <{element} type="{}" message="{} warning(s)">{}</{element}>"#,
+                kinds.join(","),
+                report.warnings.len(),
+                details.join("\n")
+            );
+        }
+        let _ = writeln!(out, "    </testcase>");
+    }
+    let _ = writeln!(out, "  </testsuite>");
+    let _ = writeln!(out, "</testsuites>");
+    out
+}
+
+/// A SARIF 2.1.0 log with one result per parse error or warning. Rule IDs
+/// are the error and warning kinds.
+pub(super) fn sarif(reports: &[FileReport]) -> Result<String> {
+    let mut rules: Vec<&str> = Vec::new();
+    let mut results = Vec::new();
+    for report in reports {
+        let problems = report
+            .error
+            .iter()
+            .map(|e| (e, "error"))
+            .chain(report.warnings.iter().map(|w| {
+                let level = if w.denied { "error" } else { "warning" };
+                (w, level)
+            }));
+        for (problem, level) in problems {
+            if !rules.contains(&problem.kind.as_str()) {
+                rules.push(&problem.kind);
+            }
+            results.push(json!({
+                "ruleId": problem.kind,
+                "level": level,
+                "message": { "text": problem.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri(&report.file) },
+                    },
+                }],
+            }));
+        }
+    }
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "version": "2.1.0",
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "puz",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                    "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                },
+            },
+            "results": results,
+        }],
+    }))?)
+}
+
+/// Escape text for an XML attribute or element.
+///
+/// Characters XML 1.0 doesn't allow at all, even as references (the C0
+/// controls other than tab, newline, and carriage return, and U+FFFE/U+FFFF),
+/// become U+FFFD. They turn up in clue text and error messages from corrupt
+/// files, and a single one would make the whole report unreadable.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' => out.push_str("&#9;"),
+            '\n' => out.push_str("&#10;"),
+            '\r' => out.push_str("&#13;"),
+            '\u{0}'..='\u{1F}' | '\u{FFFE}' | '\u{FFFF}' => out.push('\u{FFFD}'),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// A file path as a relative URI reference: `/`-separated, with anything
+/// outside the unreserved characters percent-encoded.
+fn uri(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    for b in path.replace('\\', "/").bytes() {
+        if b.is_ascii_alphanumeric() || b"-._~/".contains(&b) {
+            out.push(char::from(b));
+        } else {
+            let _ = write!(out, "%{b:02X}");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_markup() {
+        assert_eq!(
+            escape(r#"<a href="x">Tom & Jerry's</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&apos;s&lt;/a&gt;"
+        );
+        // Whitespace that attributes would otherwise normalize is kept.
+        assert_eq!(escape("a\tb\nc\r"), "a&#9;b&#10;c&#13;");
+        assert_eq!(escape("Café ✓"), "Café ✓");
+    }
+
+    #[test]
+    fn test_escape_strips_forbidden_characters() {
+        assert_eq!(
+            escape("a\0b\u{1}c\u{1B}[0m"),
+            "a\u{FFFD}b\u{FFFD}c\u{FFFD}[0m"
+        );
+        assert_eq!(escape("\u{FFFE}\u{FFFF}"), "\u{FFFD}\u{FFFD}");
+        // DEL and the C1 controls are allowed in XML 1.0.
+        assert_eq!(escape("\u{7F}\u{85}"), "\u{7F}\u{85}");
+    }
+}
@@ -0,0 +1,312 @@
+//! Where puzzles are read from: loose `.puz` files, `.puz` members of `.zip`,
+//! `.tar`, `.tar.gz`, and `.tgz` archives, and stdin. Also where the commands
+//! that write a single file send it, which may be stdout.
+//!
+//! An archive member is named like `pack.zip!/2024/jan01.puz`: the archive's
+//! path, `!/`, then the member's path inside it. The directory-walking
+//! commands report members under that name, and the single-file commands
+//! accept it in place of a path.
+//!
+//! A path of `-` is stdin for [`read`] and stdout for [`write`].
+
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, SyncSender};
+
+/// Between an archive's path and a member's path in a name.
+const SEPARATOR: &str = "!/";
+
+/// Archive members read ahead of the commands processing them.
+const READ_AHEAD: usize = 256;
+
+/// The largest input read as one puzzle, in bytes. Real `.puz` files are a few
+/// kilobytes; the cap keeps a corrupt file or a zip or tar bomb from being read
+/// into memory whole. It plays the role of [`ParseOptions::max_file_size`],
+/// applied before the bytes reach the parser.
+///
+/// [`ParseOptions::max_file_size`]: puz_parse::ParseOptions::max_file_size
+pub(crate) const MAX_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Read all of `reader`, failing with [`PuzError::FileTooLarge`] once it
+/// passes [`MAX_FILE_SIZE`]. At most one byte past the limit is read.
+///
+/// [`PuzError::FileTooLarge`]: puz_parse::PuzError::FileTooLarge
+fn read_capped(reader: impl Read) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    reader.take(MAX_FILE_SIZE + 1).read_to_end(&mut data)?;
+    if data.len() as u64 > MAX_FILE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            puz_parse::PuzError::FileTooLarge {
+                limit: MAX_FILE_SIZE,
+            },
+        ));
+    }
+    Ok(data)
+}
+
+#[derive(Clone, Copy)]
+enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveKind {
+    fn of(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveKind::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else {
+            None
+        }
+    }
+}
+
+fn is_puz(name: &str) -> bool {
+    Path::new(name).extension().and_then(|e| e.to_str()) == Some("puz")
+}
+
+/// One `.puz` file to process.
+#[derive(Debug)]
+pub(crate) enum Input {
+    /// A loose file, read when it is processed.
+    File(PathBuf),
+    /// An archive member, read while walking its archive. An archive that
+    /// can't be opened or read appears as one member named after the archive,
+    /// holding the error.
+    Member {
+        name: String,
+        data: io::Result<Vec<u8>>,
+    },
+}
+
+impl Input {
+    /// The path to report for this input.
+    pub(crate) fn name(&self) -> Cow<'_, str> {
+        match self {
+            Input::File(path) => path.to_string_lossy(),
+            Input::Member { name, .. } => Cow::Borrowed(name),
+        }
+    }
+
+    /// The file on disk, for a loose file.
+    pub(crate) fn path(&self) -> Option<&Path> {
+        match self {
+            Input::File(path) => Some(path),
+            Input::Member { .. } => None,
+        }
+    }
+
+    /// The file's bytes.
+    pub(crate) fn read(&self) -> io::Result<Cow<'_, [u8]>> {
+        match self {
+            Input::File(path) => File::open(path).and_then(read_capped).map(Cow::Owned),
+            Input::Member { data: Ok(data), .. } => Ok(Cow::Borrowed(data)),
+            Input::Member { data: Err(e), .. } => Err(io::Error::new(e.kind(), e.to_string())),
+        }
+    }
+}
+
+/// The `.puz` files and archives under `dir`, sorted so output order is
+/// stable. Fails if there are none, before a command creates any output.
+///
+/// Shared by the directory-walking commands (`validate`, `export`, `search`,
+/// `stats`, `dedupe`), which pass the result to [`expand`].
+pub(crate) fn find(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut out = Vec::new();
+    collect_into(dir, &mut out);
+    if out.is_empty() {
+        anyhow::bail!("no .puz files or archives found under {}", dir.display());
+    }
+    out.sort();
+    Ok(out)
+}
+
+fn collect_into(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        // Use the directory entry's file type, which comes from `readdir` and
+        // avoids an extra `stat` syscall per entry (the old `path.is_dir()`
+        // stat'd all 48k+ files). Fall back to a stat only if the type is
+        // unknown (rare, e.g. some network filesystems).
+        let is_dir = match entry.file_type() {
+            Ok(ft) => ft.is_dir(),
+            Err(_) => entry.path().is_dir(),
+        };
+        let path = entry.path();
+        if is_dir {
+            collect_into(&path, out);
+        } else if is_puz(&path.to_string_lossy()) || ArchiveKind::of(&path).is_some() {
+            out.push(path);
+        }
+    }
+}
+
+/// Every `.puz` input in `paths`: loose files in order, and each archive's
+/// `.puz` members in the order the archive stores them.
+///
+/// Archives are read on a background thread a bounded number of members
+/// ahead, so a large archive is never unpacked to disk or held in memory
+/// whole.
+pub(crate) fn expand(paths: Vec<PathBuf>) -> impl Iterator<Item = Input> {
+    let (tx, rx) = mpsc::sync_channel(READ_AHEAD);
+    std::thread::spawn(move || {
+        for path in paths {
+            let sent = match ArchiveKind::of(&path) {
+                Some(kind) => send_members(&path, kind, &tx),
+                None => tx.send(Input::File(path)).is_ok(),
+            };
+            // The receiver is gone: the command stopped early.
+            if !sent {
+                return;
+            }
+        }
+    });
+    rx.into_iter()
+}
+
+/// Send each `.puz` member of the archive at `path`. Returns `false` once the
+/// receiver has hung up.
+fn send_members(path: &Path, kind: ArchiveKind, tx: &SyncSender<Input>) -> bool {
+    let archive = path.display();
+    let mut send = |member: Option<&str>, data: io::Result<Vec<u8>>| {
+        let name = match member {
+            Some(member) => format!("{archive}{SEPARATOR}{member}"),
+            None => archive.to_string(),
+        };
+        tx.send(Input::Member { name, data }).is_ok()
+    };
+    let result = match kind {
+        ArchiveKind::Zip => zip_members(path, |_| true, &mut send),
+        ArchiveKind::Tar | ArchiveKind::TarGz => tar_members(path, kind, |_| true, &mut send),
+    };
+    match result {
+        Ok(sent) => sent,
+        Err(e) => send(None, Err(e)),
+    }
+}
+
+/// Read and send each `.puz` member of a zip archive that `wanted` accepts;
+/// the others are skipped without being decompressed.
+fn zip_members(
+    path: &Path,
+    wanted: impl Fn(&str) -> bool,
+    send: &mut impl FnMut(Option<&str>, io::Result<Vec<u8>>) -> bool,
+) -> io::Result<bool> {
+    let mut zip = zip::ZipArchive::new(BufReader::new(File::open(path)?))?;
+    for i in 0..zip.len() {
+        let mut member = zip.by_index(i)?;
+        if !member.is_file() || !is_puz(member.name()) || !wanted(member.name()) {
+            continue;
+        }
+        let data = read_capped(&mut member);
+        if !send(Some(member.name()), data) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Like [`zip_members`], for a tar or gzipped tar archive.
+fn tar_members(
+    path: &Path,
+    kind: ArchiveKind,
+    wanted: impl Fn(&str) -> bool,
+    send: &mut impl FnMut(Option<&str>, io::Result<Vec<u8>>) -> bool,
+) -> io::Result<bool> {
+    let file = BufReader::new(File::open(path)?);
+    let reader: Box<dyn Read> = match kind {
+        ArchiveKind::TarGz => Box::new(GzDecoder::new(file)),
+        _ => Box::new(file),
+    };
+    let mut tar = tar::Archive::new(reader);
+    for member in tar.entries()? {
+        let mut member = member?;
+        let name = member.path()?.to_string_lossy().into_owned();
+        if !member.header().entry_type().is_file() || !is_puz(&name) || !wanted(&name) {
+            continue;
+        }
+        let data = read_capped(&mut member);
+        if !send(Some(&name), data) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Read one puzzle file for the single-file commands: a path on disk, an
+/// archive member named `archive!/member`, or `-` for stdin.
+pub(crate) fn read(path: &Path) -> Result<Vec<u8>> {
+    if is_stdio(path) {
+        return read_capped(io::stdin().lock()).context("failed to read stdin");
+    }
+    let read = || -> io::Result<Vec<u8>> {
+        let Some((archive, kind, member)) = split_member(path) else {
+            return File::open(path).and_then(read_capped);
+        };
+        let mut found = None;
+        let wanted = |name: &str| name == member;
+        // Only the wanted member is read, so the first one sent is it.
+        let mut take = |_: Option<&str>, data: io::Result<Vec<u8>>| {
+            found = Some(data);
+            false
+        };
+        match kind {
+            ArchiveKind::Zip => zip_members(archive, wanted, &mut take)?,
+            ArchiveKind::Tar | ArchiveKind::TarGz => tar_members(archive, kind, wanted, &mut take)?,
+        };
+        found.unwrap_or_else(|| {
+            Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no member {member} in {}", archive.display()),
+            ))
+        })
+    };
+    read().with_context(|| format!("failed to read {}", path.display()))
+}
+
+/// Write `data` to the file at `path`, or to stdout if `path` is `-`.
+pub(crate) fn write(path: &Path, data: impl AsRef<[u8]>) -> Result<()> {
+    if is_stdio(path) {
+        let mut out = io::stdout().lock();
+        out.write_all(data.as_ref())
+            .and_then(|()| out.flush())
+            .context("failed to write stdout")?;
+        return Ok(());
+    }
+    std::fs::write(path, data).with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Whether `path` is `-`, for stdin or stdout.
+pub(crate) fn is_stdio(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// Whether `path` is a file on disk, rather than stdin or an archive member.
+pub(crate) fn is_disk_path(path: &Path) -> bool {
+    !is_stdio(path) && split_member(path).is_none()
+}
+
+/// Split `archive!/member` into the archive, its kind, and the member, if
+/// `path` names an archive member.
+fn split_member(path: &Path) -> Option<(&Path, ArchiveKind, &str)> {
+    let text = path.to_str()?;
+    text.match_indices(SEPARATOR).find_map(|(i, _)| {
+        let archive = Path::new(&text[..i]);
+        let kind = ArchiveKind::of(archive)?;
+        archive
+            .is_file()
+            .then(|| (archive, kind, &text[i + SEPARATOR.len()..]))
+    })
+}
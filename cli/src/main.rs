@@ -1,12 +1,17 @@
 //! `puz`: parse and inspect `.puz` crossword puzzle files.
 
 mod commands;
+mod input;
+mod pdf;
 mod render;
 
 use anyhow::Result;
 use clap::{ArgAction, Parser, Subcommand};
 
-use commands::{dump, export, inspect, parse_json, validate};
+use commands::{
+    build, convert, dedupe, diff, dump, edit, export, html, inspect, parse_json, play, print,
+    search, show, stats, text, validate,
+};
 
 #[derive(Parser)]
 #[command(
@@ -34,7 +39,7 @@ struct Cli {
     #[arg(short = 'V', long, action = ArgAction::Version)]
     version: Option<bool>,
 
-    /// puzzle files to parse to JSON
+    /// puzzle files to parse to JSON (- for stdin)
     #[arg(value_name = "PUZZLE", num_args = 1..)]
     files: Vec<String>,
 
@@ -60,12 +65,24 @@ enum Command {
     /// parse puzzles to JSON (same as `puz FILES...`)
     Parse(parse_json::ParseArgs),
 
+    /// build a .puz file from a TOML or JSON spec
+    Build(build::BuildArgs),
+
+    /// convert a puzzle between .puz and JSON
+    Convert(convert::ConvertArgs),
+
     /// validate every .puz file under a directory
     Validate(validate::ValidateArgs),
 
-    /// export clue/answer pairs from a directory as JSON Lines
+    /// export clue/answer pairs from a directory as JSON Lines, CSV, Parquet, or SQLite
     Export(export::ExportArgs),
 
+    /// edit a puzzle's title, author, copyright, notes, or clues in place
+    Edit(edit::EditArgs),
+
+    /// show what changed between two versions of a puzzle
+    Diff(diff::DiffArgs),
+
     /// show a file's raw structure, even if it fails to parse
     Dump {
         #[command(subcommand)]
@@ -77,6 +94,30 @@ enum Command {
         #[command(subcommand)]
         what: inspect::InspectKind,
     },
+
+    /// print a puzzle as a newspaper-style PDF page
+    Print(print::PrintArgs),
+
+    /// export a puzzle as a self-contained interactive HTML page
+    Html(html::HtmlArgs),
+
+    /// solve a puzzle interactively in the terminal
+    Play(play::PlayArgs),
+
+    /// show a puzzle's grid and clues the way a solver sees them
+    Show(show::ShowArgs),
+
+    /// export a puzzle as a Markdown or plain-text document
+    Text(text::TextArgs),
+
+    /// search a directory of puzzles for clues or answers
+    Search(search::SearchArgs),
+
+    /// summary statistics over a directory of puzzles
+    Stats(stats::StatsArgs),
+
+    /// find duplicate and near-duplicate puzzles in a directory
+    Dedupe(dedupe::DedupeArgs),
 }
 
 fn main() -> Result<()> {
@@ -86,10 +127,22 @@ fn main() -> Result<()> {
 
     match cli.command {
         Some(Command::Parse(args)) => parse_json::run(args),
+        Some(Command::Build(args)) => build::run(args),
+        Some(Command::Convert(args)) => convert::run(args),
         Some(Command::Validate(args)) => validate::run(args),
         Some(Command::Export(args)) => export::run(args),
+        Some(Command::Edit(args)) => edit::run(args),
+        Some(Command::Diff(args)) => diff::run(args),
         Some(Command::Dump { what }) => dump::run(what),
         Some(Command::Inspect { what }) => inspect::run(what),
+        Some(Command::Print(args)) => print::run(args),
+        Some(Command::Html(args)) => html::run(args),
+        Some(Command::Play(args)) => play::run(args),
+        Some(Command::Show(args)) => show::run(args),
+        Some(Command::Text(args)) => text::run(args),
+        Some(Command::Search(args)) => search::run(args),
+        Some(Command::Stats(args)) => stats::run(args),
+        Some(Command::Dedupe(args)) => dedupe::run(args),
         None => {
             // Bare `puz FILES...` behaves like `puz parse FILES...`.
             parse_json::run(parse_json::ParseArgs {
@@ -0,0 +1,365 @@
+//! A minimal PDF writer for printable puzzle output.
+//!
+//! Produces PDF 1.4 documents using only the standard Helvetica and
+//! Helvetica-Bold fonts, which every PDF viewer provides, so nothing has to be
+//! embedded and no external renderer is needed. Text is encoded as
+//! WinAnsiEncoding, the PDF name for Windows-1252, the same code page `.puz`
+//! strings use. Only the drawing operations the `print` command needs are
+//! implemented: text, lines, rectangles, and circles.
+//!
+//! Coordinates are PDF points (1/72 inch) with the origin at the bottom-left
+//! of the page.
+
+use std::fmt::Write as _;
+
+/// A page size in points.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PageSize {
+    pub(crate) width: f32,
+    pub(crate) height: f32,
+}
+
+/// US Letter, 8.5 x 11 in.
+pub(crate) const LETTER: PageSize = PageSize {
+    width: 612.0,
+    height: 792.0,
+};
+
+/// ISO A4, 210 x 297 mm.
+pub(crate) const A4: PageSize = PageSize {
+    width: 595.0,
+    height: 842.0,
+};
+
+/// One of the two built-in fonts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        }
+    }
+}
+
+/// A document under construction: a list of pages, each a content stream.
+#[derive(Debug)]
+pub(crate) struct Document {
+    size: PageSize,
+    pages: Vec<Page>,
+}
+
+/// A single page's drawing operations.
+#[derive(Debug, Default)]
+pub(crate) struct Page {
+    content: Vec<u8>,
+}
+
+impl Document {
+    pub(crate) fn new(size: PageSize) -> Self {
+        Self {
+            size,
+            pages: Vec::new(),
+        }
+    }
+
+    pub(crate) fn size(&self) -> PageSize {
+        self.size
+    }
+
+    /// Append a blank page and return it for drawing.
+    pub(crate) fn add_page(&mut self) -> &mut Page {
+        self.pages.push(Page::default());
+        self.pages.last_mut().expect("page just pushed")
+    }
+
+    pub(crate) fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Borrow an existing page for further drawing.
+    pub(crate) fn page_mut(&mut self, index: usize) -> &mut Page {
+        &mut self.pages[index]
+    }
+
+    /// Serialize the document.
+    ///
+    /// Object layout: 1 catalog, 2 page tree, 3-4 fonts, then a page object
+    /// and its content stream for each page.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut out: Vec<u8> = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::new();
+
+        let page_ids: Vec<usize> = (0..self.pages.len()).map(|i| 5 + 2 * i).collect();
+        let kids = page_ids
+            .iter()
+            .map(|id| format!("{id} 0 R"))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            format!(
+                "<< /Type /Pages /Kids [{kids}] /Count {} /MediaBox [0 0 {} {}] >>",
+                self.pages.len(),
+                num(self.size.width),
+                num(self.size.height)
+            )
+            .into_bytes(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_vec(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_vec(),
+        ];
+        for (page, &id) in self.pages.iter().zip(&page_ids) {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                    id + 1
+                )
+                .into_bytes(),
+            );
+            let mut stream = format!("<< /Length {} >>\nstream\n", page.content.len()).into_bytes();
+            stream.extend_from_slice(&page.content);
+            stream.extend_from_slice(b"\nendstream");
+            objects.push(stream);
+        }
+
+        for (i, body) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(body);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref = out.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for off in offsets {
+            let _ = writeln!(trailer, "{off:010} 00000 n ");
+        }
+        let _ = write!(
+            trailer,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        );
+        out.extend_from_slice(trailer.as_bytes());
+        out
+    }
+}
+
+impl Page {
+    fn op(&mut self, s: &str) {
+        self.content.extend_from_slice(s.as_bytes());
+        self.content.push(b'\n');
+    }
+
+    /// Draw `text` with its baseline starting at `(x, y)`.
+    pub(crate) fn text(&mut self, x: f32, y: f32, font: Font, size: f32, text: &str) {
+        self.op(&format!(
+            "BT /{} {} Tf {} {} Td",
+            font.resource(),
+            num(size),
+            num(x),
+            num(y)
+        ));
+        self.content.push(b'(');
+        for b in win_ansi(text) {
+            match b {
+                b'(' | b')' | b'\\' => {
+                    self.content.push(b'\\');
+                    self.content.push(b);
+                }
+                0x20..=0x7E => self.content.push(b),
+                _ => self
+                    .content
+                    .extend_from_slice(format!("\\{b:03o}").as_bytes()),
+            }
+        }
+        self.content.extend_from_slice(b") Tj ET\n");
+    }
+
+    /// Draw `text` horizontally centered on `cx`.
+    pub(crate) fn text_centered(&mut self, cx: f32, y: f32, font: Font, size: f32, text: &str) {
+        let x = cx - text_width(text, font, size) / 2.0;
+        self.text(x, y, font, size, text);
+    }
+
+    /// Draw `text` so it ends at `right`.
+    pub(crate) fn text_right(&mut self, right: f32, y: f32, font: Font, size: f32, text: &str) {
+        let x = right - text_width(text, font, size);
+        self.text(x, y, font, size, text);
+    }
+
+    /// Stroke a line segment.
+    pub(crate) fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, width: f32) {
+        self.op(&format!(
+            "{} w {} {} m {} {} l S",
+            num(width),
+            num(x1),
+            num(y1),
+            num(x2),
+            num(y2)
+        ));
+    }
+
+    /// Fill a rectangle (lower-left corner at `(x, y)`) with a gray level,
+    /// where 0 is black and 1 is white.
+    pub(crate) fn fill_rect(&mut self, x: f32, y: f32, w: f32, h: f32, gray: f32) {
+        self.op(&format!(
+            "{} g {} {} {} {} re f 0 g",
+            num(gray),
+            num(x),
+            num(y),
+            num(w),
+            num(h)
+        ));
+    }
+
+    /// Stroke a rectangle outline (lower-left corner at `(x, y)`).
+    pub(crate) fn stroke_rect(&mut self, x: f32, y: f32, w: f32, h: f32, width: f32) {
+        self.op(&format!(
+            "{} w {} {} {} {} re S",
+            num(width),
+            num(x),
+            num(y),
+            num(w),
+            num(h)
+        ));
+    }
+
+    /// Stroke a circle, approximated by four cubic Bezier arcs.
+    pub(crate) fn stroke_circle(&mut self, cx: f32, cy: f32, r: f32, width: f32) {
+        // Control-point distance for a quarter circle.
+        let k = 0.552_284_8 * r;
+        self.op(&format!(
+            "{} w {} {} m {} {} {} {} {} {} c {} {} {} {} {} {} c {} {} {} {} {} {} c {} {} {} {} {} {} c S",
+            num(width),
+            num(cx + r),
+            num(cy),
+            num(cx + r),
+            num(cy + k),
+            num(cx + k),
+            num(cy + r),
+            num(cx),
+            num(cy + r),
+            num(cx - k),
+            num(cy + r),
+            num(cx - r),
+            num(cy + k),
+            num(cx - r),
+            num(cy),
+            num(cx - r),
+            num(cy - k),
+            num(cx - k),
+            num(cy - r),
+            num(cx),
+            num(cy - r),
+            num(cx + k),
+            num(cy - r),
+            num(cx + r),
+            num(cy - k),
+            num(cx + r),
+            num(cy),
+        ));
+    }
+}
+
+/// Format a coordinate compactly: two decimals, trailing zeros trimmed.
+fn num(v: f32) -> String {
+    let s = format!("{v:.2}");
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+/// Encode text as WinAnsiEncoding bytes, replacing anything Windows-1252 can't
+/// represent with `?`.
+fn win_ansi(text: &str) -> Vec<u8> {
+    let mut buf = [0u8; 4];
+    text.chars()
+        .map(|c| {
+            puz_parse::encoding::encode_windows_1252(c.encode_utf8(&mut buf), "pdf text")
+                .ok()
+                .and_then(|bytes| bytes.first().copied())
+                .unwrap_or(b'?')
+        })
+        .collect()
+}
+
+/// The width of `text` in points when set in `font` at `size`.
+pub(crate) fn text_width(text: &str, font: Font, size: f32) -> f32 {
+    let units: u32 = win_ansi(text).iter().map(|&b| glyph_width(b, font)).sum();
+    units as f32 * size / 1000.0
+}
+
+/// Split `text` into lines no wider than `max_width`, breaking at spaces.
+///
+/// A single word wider than the line is placed on its own line unbroken.
+pub(crate) fn wrap(text: &str, font: Font, size: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{current} {word}")
+        };
+        if current.is_empty() || text_width(&candidate, font, size) <= max_width {
+            current = candidate;
+        } else {
+            lines.push(std::mem::take(&mut current));
+            current = word.to_string();
+        }
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Advance width of a WinAnsi byte in 1/1000 em, from the standard Adobe
+/// Helvetica metrics. Bytes outside printable ASCII use an average width.
+fn glyph_width(b: u8, font: Font) -> u32 {
+    const REGULAR: [u16; 95] = [
+        278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278,
+        278, // ' '../
+        556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584,
+        556, // 0..?
+        1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722,
+        778, // @..O
+        667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469,
+        556, // P.._
+        333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556,
+        556, // `..o
+        556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584, // p..~
+    ];
+    const BOLD: [u16; 95] = [
+        278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278,
+        278, // ' '../
+        556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584,
+        611, // 0..?
+        975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722,
+        778, // @..O
+        667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584,
+        556, // P.._
+        333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611,
+        611, // `..o
+        611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584, // p..~
+    ];
+    let table = match font {
+        Font::Regular => &REGULAR,
+        Font::Bold => &BOLD,
+    };
+    match b {
+        0x20..=0x7E => table[(b - 0x20) as usize] as u32,
+        _ => 556,
+    }
+}
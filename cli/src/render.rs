@@ -5,7 +5,7 @@
 //! `dump` and `inspect`. Command modules extract data (mostly via
 //! [`puz_parse::raw`]) and hand it here for display.
 
-use std::io::IsTerminal;
+use std::io::{self, IsTerminal, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use comfy_table::{Table, presets};
@@ -29,7 +29,9 @@ pub(crate) fn init_styling(no_color_flag: bool) {
     owo_colors::set_override(styled);
 }
 
-fn styled() -> bool {
+/// Whether styled output is enabled. Full-screen commands use this to pick
+/// between colors and plain reverse-video/ASCII rendering.
+pub(crate) fn styled() -> bool {
     STYLED.load(Ordering::Relaxed)
 }
 
@@ -65,6 +67,15 @@ pub(crate) fn green(s: impl std::fmt::Display) -> String {
     }
 }
 
+/// Red text (plain when styling is disabled).
+pub(crate) fn red(s: impl std::fmt::Display) -> String {
+    if styled() {
+        s.red().to_string()
+    } else {
+        s.to_string()
+    }
+}
+
 /// Yellow text (plain when styling is disabled).
 pub(crate) fn yellow(s: impl std::fmt::Display) -> String {
     if styled() {
@@ -74,6 +85,59 @@ pub(crate) fn yellow(s: impl std::fmt::Display) -> String {
     }
 }
 
+/// Text on a gray background, for shaded squares (plain when styling is
+/// disabled).
+pub(crate) fn shaded(s: impl std::fmt::Display) -> String {
+    if styled() {
+        s.on_bright_black().to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+/// A diff of one text field: its label, then the old text in red and the new
+/// text in green, a line each per line of text. A side that is `None` (an
+/// added or removed field) prints nothing.
+pub(crate) fn print_change(
+    out: &mut dyn Write,
+    label: &str,
+    old: Option<&str>,
+    new: Option<&str>,
+) -> io::Result<()> {
+    writeln!(out, "{}", bold(format!("{label}:")))?;
+    for line in old.iter().flat_map(|text| text.lines()) {
+        writeln!(out, "{}", red(format!("- {line}")))?;
+    }
+    for line in new.iter().flat_map(|text| text.lines()) {
+        writeln!(out, "{}", green(format!("+ {line}")))?;
+    }
+    Ok(())
+}
+
+/// Bytes shown per line of a hex dump.
+pub(crate) const HEX_WIDTH: usize = 16;
+
+/// One line of a hex dump: the offset, up to [`HEX_WIDTH`] bytes in hex, and
+/// the same bytes as ASCII, padded so that anything appended lines up.
+pub(crate) fn hex_line(offset: usize, chunk: &[u8]) -> String {
+    let hex: Vec<String> = chunk.iter().map(|b| format!("{b:02x}")).collect();
+    let ascii: String = chunk
+        .iter()
+        .map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            }
+        })
+        .collect();
+    format!(
+        "{offset:08x}  {:<width$}  {ascii:<HEX_WIDTH$}",
+        hex.join(" "),
+        width = HEX_WIDTH * 3 - 1,
+    )
+}
+
 /// A borderless table (key/value metadata blocks and numbered lists).
 pub(crate) fn borderless_table() -> Table {
     let mut table = Table::new();
@@ -111,12 +175,13 @@ pub(crate) fn byte_repr(b: u8) -> String {
 
 /// Print a byte grid as one line per row, each cell rendered as a character,
 /// under a dimmed label.
-pub(crate) fn print_grid(label: &str, grid: &[Vec<u8>]) {
-    println!("{}", dim(format!("{label}:")));
+pub(crate) fn print_grid(out: &mut dyn Write, label: &str, grid: &[Vec<u8>]) -> io::Result<()> {
+    writeln!(out, "{}", dim(format!("{label}:")))?;
     for (i, row) in grid.iter().enumerate() {
         let rendered: String = row.iter().map(|&b| render_cell(b)).collect();
-        println!("  {i:>2} {rendered}");
+        writeln!(out, "  {i:>2} {rendered}")?;
     }
+    Ok(())
 }
 
 /// The distinct byte values in a grid, sorted, each shown as `char(hex)`.
@@ -86,6 +86,7 @@ fn plain(size: usize) -> Puzzle {
             rebus: None,
             circles: None,
             given: None,
+            timer: None,
         },
     }
 }
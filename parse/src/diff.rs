@@ -0,0 +1,473 @@
+//! Field-by-field comparison of two puzzles.
+//!
+//! [`Puzzle::diff`] reports what an editor would review between two drafts:
+//! metadata, individual squares of the solution and fill grids, clues by
+//! number and direction, and the extensions. Each change carries the old and
+//! new values, so a caller can print it without looking at either puzzle.
+
+use crate::{Direction, Puzzle};
+
+/// Everything that differs between two puzzles, from [`Puzzle::diff`].
+///
+/// Each list is empty when that part of the puzzles matches.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct PuzzleDiff {
+    /// Changed metadata fields, in [`PuzzleInfo`](crate::PuzzleInfo) order.
+    pub info: Vec<FieldChange>,
+    /// Changed squares, solution grid first, each grid in reading order.
+    pub cells: Vec<CellChange>,
+    /// Changed, added, and removed clues, across then down, by number.
+    pub clues: Vec<ClueChange>,
+    /// Added, removed, and changed extensions.
+    pub extensions: Vec<ExtensionChange>,
+}
+
+impl PuzzleDiff {
+    /// Whether the puzzles match in everything compared.
+    pub fn is_empty(&self) -> bool {
+        self.info.is_empty()
+            && self.cells.is_empty()
+            && self.clues.is_empty()
+            && self.extensions.is_empty()
+    }
+}
+
+/// A metadata field whose value differs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldChange {
+    /// The [`PuzzleInfo`](crate::PuzzleInfo) field, e.g. `title` or `width`.
+    pub field: String,
+    /// The old value, as text.
+    pub old: String,
+    /// The new value, as text.
+    pub new: String,
+}
+
+/// Which grid a [`CellChange`] is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "lowercase"))]
+pub enum GridKind {
+    /// The solution grid.
+    Solution,
+    /// The blank (fill) grid: the solver's progress.
+    Fill,
+}
+
+/// A square whose character differs between the two puzzles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct CellChange {
+    /// The grid the square is in.
+    pub grid: GridKind,
+    /// Zero-based row.
+    pub row: usize,
+    /// Zero-based column.
+    pub col: usize,
+    /// The old character, or `None` if the old grid has no such square.
+    pub old: Option<char>,
+    /// The new character, or `None` if the new grid has no such square.
+    pub new: Option<char>,
+}
+
+/// A clue that was changed, added (`old` is `None`), or removed (`new` is
+/// `None`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClueChange {
+    /// Whether the clue is across or down.
+    pub direction: Direction,
+    /// The clue number.
+    pub number: u16,
+    /// The old text, if the old puzzle has this clue.
+    pub old: Option<String>,
+    /// The new text, if the new puzzle has this clue.
+    pub new: Option<String>,
+}
+
+/// One of the optional extensions in [`Extensions`](crate::Extensions).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "lowercase"))]
+pub enum Extension {
+    /// Rebus squares.
+    Rebus,
+    /// Circled squares.
+    Circles,
+    /// Given squares.
+    Given,
+    /// The saved solving timer.
+    Timer,
+}
+
+/// How an extension differs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "lowercase"))]
+pub enum Change {
+    /// Only the new puzzle has it.
+    Added,
+    /// Only the old puzzle has it.
+    Removed,
+    /// Both have it, with different contents.
+    Changed,
+}
+
+/// An extension that was added, removed, or changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExtensionChange {
+    /// The extension.
+    pub extension: Extension,
+    /// How it differs.
+    pub change: Change,
+    /// The `(row, col)` squares it differs at, in reading order: squares
+    /// circled, given, or holding rebus text on one side and not the other
+    /// (or, for a rebus, holding different text). Empty for the timer.
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl Puzzle {
+    /// Compare this puzzle (the old one) with `other` (the new one).
+    ///
+    /// Metadata is compared field by field, the solution and fill grids
+    /// square by square, and clues by number and direction. Extensions are
+    /// reported as added, removed, or changed. The raw clue list and anything
+    /// not in the data model, such as checksums, are not compared.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use puz_parse::{GridKind, Puzzle};
+    ///
+    /// let draft = Puzzle::new().grid(["CAT", "ORE"]).title("Draft");
+    /// let revised = draft.clone().title("Final").grid(["CAT", "ARE"]);
+    ///
+    /// let diff = draft.diff(&revised);
+    /// assert_eq!(diff.info[0].field, "title");
+    /// let cell = &diff.cells[0];
+    /// assert_eq!((cell.grid, cell.row, cell.col), (GridKind::Solution, 1, 0));
+    /// assert_eq!((cell.old, cell.new), (Some('O'), Some('A')));
+    /// ```
+    pub fn diff(&self, other: &Puzzle) -> PuzzleDiff {
+        PuzzleDiff {
+            info: diff_info(self, other),
+            cells: [
+                (
+                    GridKind::Solution,
+                    &self.grid.solution,
+                    &other.grid.solution,
+                ),
+                (GridKind::Fill, &self.grid.blank, &other.grid.blank),
+            ]
+            .into_iter()
+            .flat_map(|(grid, old, new)| diff_grid(grid, old, new))
+            .collect(),
+            clues: diff_clues(self, other),
+            extensions: diff_extensions(self, other),
+        }
+    }
+}
+
+fn diff_info(old: &Puzzle, new: &Puzzle) -> Vec<FieldChange> {
+    let (a, b) = (&old.info, &new.info);
+    let fields = [
+        ("title", a.title.clone(), b.title.clone()),
+        ("author", a.author.clone(), b.author.clone()),
+        ("copyright", a.copyright.clone(), b.copyright.clone()),
+        ("notes", a.notes.clone(), b.notes.clone()),
+        ("width", a.width.to_string(), b.width.to_string()),
+        ("height", a.height.to_string(), b.height.to_string()),
+        ("version", a.version.clone(), b.version.clone()),
+        (
+            "is_scrambled",
+            a.is_scrambled.to_string(),
+            b.is_scrambled.to_string(),
+        ),
+        (
+            "is_diagramless",
+            a.is_diagramless.to_string(),
+            b.is_diagramless.to_string(),
+        ),
+    ];
+    fields
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(field, old, new)| FieldChange {
+            field: field.to_string(),
+            old,
+            new,
+        })
+        .collect()
+}
+
+/// Compare two grids over the larger of their sizes, so squares only one of
+/// them has show up with `None` on the other side.
+fn diff_grid(grid: GridKind, old: &[String], new: &[String]) -> Vec<CellChange> {
+    let rows = |g: &[String]| -> Vec<Vec<char>> { g.iter().map(|r| r.chars().collect()).collect() };
+    let (old, new) = (rows(old), rows(new));
+    let height = old.len().max(new.len());
+    let mut changes = Vec::new();
+    for row in 0..height {
+        let old_row = old.get(row).map_or(&[][..], Vec::as_slice);
+        let new_row = new.get(row).map_or(&[][..], Vec::as_slice);
+        for col in 0..old_row.len().max(new_row.len()) {
+            let (a, b) = (old_row.get(col).copied(), new_row.get(col).copied());
+            if a != b {
+                changes.push(CellChange {
+                    grid,
+                    row,
+                    col,
+                    old: a,
+                    new: b,
+                });
+            }
+        }
+    }
+    changes
+}
+
+fn diff_clues(old: &Puzzle, new: &Puzzle) -> Vec<ClueChange> {
+    let mut changes = Vec::new();
+    for (direction, a, b) in [
+        (Direction::Across, &old.clues.across, &new.clues.across),
+        (Direction::Down, &old.clues.down, &new.clues.down),
+    ] {
+        let mut numbers: Vec<u16> = a
+            .as_map()
+            .keys()
+            .chain(b.as_map().keys())
+            .copied()
+            .collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+        for number in numbers {
+            let (old, new) = (a.get(number), b.get(number));
+            if old != new {
+                changes.push(ClueChange {
+                    direction,
+                    number,
+                    old: old.map(str::to_string),
+                    new: new.map(str::to_string),
+                });
+            }
+        }
+    }
+    changes
+}
+
+fn diff_extensions(old: &Puzzle, new: &Puzzle) -> Vec<ExtensionChange> {
+    let (a, b) = (&old.extensions, &new.extensions);
+    let flag = |grid: &Option<Vec<Vec<bool>>>, r: usize, c: usize| {
+        grid.as_ref()
+            .and_then(|g| g.get(r)?.get(c).copied())
+            .unwrap_or(false)
+    };
+
+    let mut changes = Vec::new();
+    let mut push = |extension, old_present: bool, new_present: bool, same: bool, cells| {
+        let change = match (old_present, new_present) {
+            (false, true) => Change::Added,
+            (true, false) => Change::Removed,
+            (true, true) if !same => Change::Changed,
+            _ => return,
+        };
+        changes.push(ExtensionChange {
+            extension,
+            change,
+            cells,
+        });
+    };
+    push(
+        Extension::Rebus,
+        a.rebus.is_some(),
+        b.rebus.is_some(),
+        a.rebus == b.rebus,
+        differing_cells(old, new, |r, c| rebus_at(old, r, c) != rebus_at(new, r, c)),
+    );
+    push(
+        Extension::Circles,
+        a.circles.is_some(),
+        b.circles.is_some(),
+        a.circles == b.circles,
+        differing_cells(old, new, |r, c| {
+            flag(&a.circles, r, c) != flag(&b.circles, r, c)
+        }),
+    );
+    push(
+        Extension::Given,
+        a.given.is_some(),
+        b.given.is_some(),
+        a.given == b.given,
+        differing_cells(old, new, |r, c| {
+            flag(&a.given, r, c) != flag(&b.given, r, c)
+        }),
+    );
+    push(
+        Extension::Timer,
+        a.timer.is_some(),
+        b.timer.is_some(),
+        a.timer == b.timer,
+        Vec::new(),
+    );
+    changes
+}
+
+fn rebus_at(puzzle: &Puzzle, row: usize, col: usize) -> Option<&str> {
+    puzzle.extensions.rebus.as_ref()?.get(row, col)
+}
+
+/// The squares, over the larger of the two puzzles' sizes, where `differs`.
+fn differing_cells(
+    old: &Puzzle,
+    new: &Puzzle,
+    differs: impl Fn(usize, usize) -> bool,
+) -> Vec<(usize, usize)> {
+    let height = old.info.height.max(new.info.height) as usize;
+    let width = old.info.width.max(new.info.width) as usize;
+    (0..height)
+        .flat_map(|r| (0..width).map(move |c| (r, c)))
+        .filter(|&(r, c)| differs(r, c))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Rebus, Timer};
+    use std::collections::HashMap;
+
+    fn sample() -> Puzzle {
+        Puzzle::new()
+            .grid(["CAT", "A.O", "BEE"])
+            .title("Sample")
+            .author("Someone")
+    }
+
+    #[test]
+    fn test_identical_puzzles_have_an_empty_diff() {
+        assert!(sample().diff(&sample()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_metadata_fields() {
+        let revised = sample().title("Revised").diagramless(true);
+        let fields: Vec<_> = sample()
+            .diff(&revised)
+            .info
+            .into_iter()
+            .map(|f| (f.field, f.old, f.new))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("title".into(), "Sample".into(), "Revised".into()),
+                ("is_diagramless".into(), "false".into(), "true".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_separates_solution_and_fill() {
+        let mut revised = sample();
+        revised.grid.solution[2] = "BET".into();
+        revised.grid.blank[0] = "C--".into();
+        let cells = sample().diff(&revised).cells;
+        assert_eq!(
+            cells,
+            [
+                CellChange {
+                    grid: GridKind::Solution,
+                    row: 2,
+                    col: 2,
+                    old: Some('E'),
+                    new: Some('T'),
+                },
+                CellChange {
+                    grid: GridKind::Fill,
+                    row: 0,
+                    col: 0,
+                    old: Some('-'),
+                    new: Some('C'),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_covers_squares_only_one_grid_has() {
+        let wider = Puzzle::new().grid(["CATS", "A.OX", "BEES"]);
+        let cells: Vec<_> = sample()
+            .diff(&wider)
+            .cells
+            .into_iter()
+            .filter(|c| c.grid == GridKind::Solution)
+            .map(|c| (c.row, c.col, c.old, c.new))
+            .collect();
+        assert_eq!(
+            cells,
+            [
+                (0, 3, None, Some('S')),
+                (1, 3, None, Some('X')),
+                (2, 3, None, Some('S')),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_reports_changed_added_and_removed_clues() {
+        let mut revised = sample();
+        revised.clues.across.set(1, "Pet");
+        revised.clues.down.remove(2);
+        revised.clues.down.set(9, "Extra");
+        let clues: Vec<_> = sample()
+            .diff(&revised)
+            .clues
+            .into_iter()
+            .map(|c| (c.direction, c.number, c.old.is_some(), c.new))
+            .collect();
+        assert_eq!(
+            clues,
+            [
+                (Direction::Across, 1, true, Some("Pet".into())),
+                (Direction::Down, 2, true, None),
+                (Direction::Down, 9, false, Some("Extra".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_reports_extensions() {
+        let old = sample().circles(vec![vec![true, false, false]; 3]);
+        let mut new = sample()
+            .circles(vec![
+                vec![true, false, false],
+                vec![true, false, false],
+                vec![false, false, true],
+            ])
+            .rebus(Rebus {
+                grid: vec![vec![0, 0, 0], vec![0, 0, 0], vec![0, 0, 1]],
+                table: HashMap::from([(0, "EEL".to_string())]),
+            });
+        new.extensions.timer = Some(Timer {
+            elapsed_secs: 5,
+            running: false,
+        });
+        let changes: Vec<_> = old
+            .diff(&new)
+            .extensions
+            .into_iter()
+            .map(|e| (e.extension, e.change, e.cells))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                (Extension::Rebus, Change::Added, vec![(2, 2)]),
+                (Extension::Circles, Change::Changed, vec![(2, 0), (2, 2)]),
+                (Extension::Timer, Change::Added, vec![]),
+            ]
+        );
+    }
+}
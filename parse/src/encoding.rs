@@ -4,14 +4,20 @@
 //! module keeps the decode and encode halves side by side so they stay exact
 //! inverses of each other: `decode_puz_string(&encode_windows_1252(s)?) == s`
 //! for any encodable `s`.
+//!
+//! The decode and encode functions are public so tools that produce other
+//! Windows-1252-based output (or check text before writing it into a puzzle)
+//! agree with the parser and writer on exactly which characters are
+//! representable.
 
 use crate::error::PuzError;
+use crate::options::TextEncoding;
 
 /// Decode `.puz` string bytes into a `String`.
 ///
 /// Tries UTF-8 first (modern files), falling back to Windows-1252 for legacy
 /// files.
-pub(crate) fn decode_puz_string(bytes: &[u8]) -> Result<String, PuzError> {
+pub fn decode_puz_string(bytes: &[u8]) -> Result<String, PuzError> {
     if let Ok(s) = std::str::from_utf8(bytes) {
         return Ok(s.to_string());
     }
@@ -19,12 +25,21 @@ pub(crate) fn decode_puz_string(bytes: &[u8]) -> Result<String, PuzError> {
     Ok(bytes.iter().map(|&b| windows_1252_to_char(b)).collect())
 }
 
+/// Decode `.puz` string bytes as [`TextEncoding`] says to.
+pub(crate) fn decode_with(bytes: &[u8], encoding: TextEncoding) -> Result<String, PuzError> {
+    match encoding {
+        TextEncoding::Auto => decode_puz_string(bytes),
+        TextEncoding::Windows1252 => Ok(bytes.iter().map(|&b| windows_1252_to_char(b)).collect()),
+        TextEncoding::Utf8 => Ok(std::str::from_utf8(bytes)?.to_string()),
+    }
+}
+
 /// Encode a string as Windows-1252 bytes.
 ///
-/// The exact inverse of [`windows_1252_to_char`]. Returns
+/// The exact inverse of the decoder's Windows-1252 fallback. Returns
 /// [`PuzError::EncodingError`] for characters outside the Windows-1252
-/// repertoire.
-pub(crate) fn encode_windows_1252(s: &str, context: &str) -> Result<Vec<u8>, PuzError> {
+/// repertoire; `context` names the field being encoded in that error.
+pub fn encode_windows_1252(s: &str, context: &str) -> Result<Vec<u8>, PuzError> {
     let mut out = Vec::with_capacity(s.len());
     for ch in s.chars() {
         let byte = char_to_windows_1252(ch).ok_or_else(|| PuzError::EncodingError {
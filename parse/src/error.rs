@@ -23,6 +23,15 @@ pub enum PuzWarning {
         expected: u16,
         found: u16,
     },
+    /// An extension section's stored checksum did not match its data. The
+    /// section is still read, so corrupted flags or rebus text may come
+    /// through; strict parsing rejects the file instead.
+    SectionChecksumMismatch {
+        /// The section tag, e.g. `"GEXT"`.
+        section: String,
+        expected: u16,
+        found: u16,
+    },
     /// A solution cell holds a non-standard character (not a letter, digit, or
     /// black square) with no rebus entry at that position. It could be a rebus
     /// glyph the file failed to describe, or it could be corruption.
@@ -44,6 +53,48 @@ pub enum PuzWarning {
     },
 }
 
+impl PuzWarning {
+    /// Every name [`kind`](Self::kind) can return, in declaration order.
+    ///
+    /// ```
+    /// use puz_parse::PuzWarning;
+    ///
+    /// assert!(PuzWarning::KINDS.contains(&"ExtraClues"));
+    /// ```
+    pub const KINDS: &[&str] = &[
+        "SkippedExtension",
+        "EncodingIssue",
+        "DataRecovery",
+        "ScrambledPuzzle",
+        "ChecksumMismatch",
+        "SectionChecksumMismatch",
+        "UnbackedGridChar",
+        "ExtraClues",
+    ];
+
+    /// The variant name, e.g. `"ChecksumMismatch"`, for grouping and counting
+    /// warnings by type without matching on their fields.
+    ///
+    /// ```
+    /// use puz_parse::PuzWarning;
+    ///
+    /// let warning = PuzWarning::ScrambledPuzzle { version: "1.3".into() };
+    /// assert_eq!(warning.kind(), "ScrambledPuzzle");
+    /// ```
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PuzWarning::SkippedExtension { .. } => "SkippedExtension",
+            PuzWarning::EncodingIssue { .. } => "EncodingIssue",
+            PuzWarning::DataRecovery { .. } => "DataRecovery",
+            PuzWarning::ScrambledPuzzle { .. } => "ScrambledPuzzle",
+            PuzWarning::ChecksumMismatch { .. } => "ChecksumMismatch",
+            PuzWarning::SectionChecksumMismatch { .. } => "SectionChecksumMismatch",
+            PuzWarning::UnbackedGridChar { .. } => "UnbackedGridChar",
+            PuzWarning::ExtraClues { .. } => "ExtraClues",
+        }
+    }
+}
+
 /// Result type for parsing that includes warnings.
 ///
 /// This is returned by the main [`parse`](crate::parse) function and contains
@@ -143,6 +194,9 @@ pub enum PuzError {
 
     /// A requested puzzle feature is not supported by the writer
     UnsupportedFeature { feature: String },
+
+    /// The input is longer than [`ParseOptions::max_file_size`](crate::ParseOptions::max_file_size) allows
+    FileTooLarge { limit: u64 },
 }
 
 impl fmt::Display for PuzError {
@@ -231,6 +285,9 @@ impl fmt::Display for PuzError {
             PuzError::UnsupportedFeature { feature } => {
                 write!(f, "Writing is not supported for: {feature}.")
             }
+            PuzError::FileTooLarge { limit } => {
+                write!(f, "File is larger than the {limit}-byte limit.")
+            }
         }
     }
 }
@@ -257,6 +314,34 @@ impl From<std::str::Utf8Error> for PuzError {
 }
 
 impl PuzError {
+    /// The variant name, e.g. `"InvalidMagic"`, like [`PuzWarning::kind`].
+    ///
+    /// ```
+    /// use puz_parse::PuzError;
+    ///
+    /// let error = PuzError::InvalidDimensions { width: 0, height: 15 };
+    /// assert_eq!(error.kind(), "InvalidDimensions");
+    /// ```
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PuzError::InvalidMagic { .. } => "InvalidMagic",
+            PuzError::InvalidChecksum { .. } => "InvalidChecksum",
+            PuzError::InvalidDimensions { .. } => "InvalidDimensions",
+            PuzError::InvalidClueCount { .. } => "InvalidClueCount",
+            PuzError::SectionSizeMismatch { .. } => "SectionSizeMismatch",
+            PuzError::ParseError { .. } => "ParseError",
+            PuzError::IoError { .. } => "IoError",
+            PuzError::InvalidUtf8 { .. } => "InvalidUtf8",
+            PuzError::MissingData { .. } => "MissingData",
+            PuzError::UnsupportedVersion { .. } => "UnsupportedVersion",
+            PuzError::InvalidGrid { .. } => "InvalidGrid",
+            PuzError::InvalidClues { .. } => "InvalidClues",
+            PuzError::EncodingError { .. } => "EncodingError",
+            PuzError::UnsupportedFeature { .. } => "UnsupportedFeature",
+            PuzError::FileTooLarge { .. } => "FileTooLarge",
+        }
+    }
+
     /// Add position context to an existing error
     pub fn with_position(mut self, position: u64) -> Self {
         match &mut self {
@@ -336,6 +421,16 @@ impl fmt::Display for PuzWarning {
                     "Checksum mismatch in {context}: expected 0x{expected:04X}, found 0x{found:04X}. The file may be corrupted."
                 )
             }
+            PuzWarning::SectionChecksumMismatch {
+                section,
+                expected,
+                found,
+            } => {
+                write!(
+                    f,
+                    "Checksum mismatch in extension section '{section}': expected 0x{expected:04X}, found 0x{found:04X}. The section may be corrupted."
+                )
+            }
             PuzWarning::UnbackedGridChar {
                 character,
                 row,
@@ -358,3 +453,51 @@ impl fmt::Display for PuzWarning {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kinds_lists_every_warning() {
+        // Keep KINDS in step with the variants; a new one belongs in both.
+        let warnings = [
+            PuzWarning::SkippedExtension {
+                section: "GEXT".into(),
+                reason: String::new(),
+            },
+            PuzWarning::EncodingIssue {
+                context: String::new(),
+                recovered: true,
+            },
+            PuzWarning::DataRecovery {
+                field: String::new(),
+                issue: String::new(),
+            },
+            PuzWarning::ScrambledPuzzle {
+                version: "1.3".into(),
+            },
+            PuzWarning::ChecksumMismatch {
+                context: "global".into(),
+                expected: 0,
+                found: 1,
+            },
+            PuzWarning::SectionChecksumMismatch {
+                section: "GEXT".into(),
+                expected: 0,
+                found: 1,
+            },
+            PuzWarning::UnbackedGridChar {
+                character: '#',
+                row: 0,
+                col: 0,
+            },
+            PuzWarning::ExtraClues {
+                slots: 1,
+                provided: 2,
+            },
+        ];
+        let kinds: Vec<&str> = warnings.iter().map(PuzWarning::kind).collect();
+        assert_eq!(kinds, PuzWarning::KINDS);
+    }
+}
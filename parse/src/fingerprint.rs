@@ -0,0 +1,198 @@
+//! Stable content fingerprints for spotting duplicate puzzles.
+//!
+//! The same puzzle often turns up many times in an archive: under another file
+//! name, with the solver's progress saved into it, or with a stray space in
+//! the title. A fingerprint hashes what makes the puzzle itself and skips the
+//! rest, so those copies hash alike.
+//!
+//! The hash is 64-bit FNV-1a over a fixed field order. It is stable across
+//! runs, platforms, and crate versions that don't change this module, so
+//! fingerprints can be stored and compared later. It is not cryptographic.
+
+use crate::Puzzle;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Fnv(FNV_OFFSET)
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= u64::from(b);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Hash a string with a terminator, so `["ab", "c"]` and `["a", "bc"]`
+    /// differ.
+    fn field(&mut self, s: &str) {
+        self.bytes(s.as_bytes());
+        self.bytes(&[0xFF]);
+    }
+
+    /// Hash text with its whitespace normalized: trimmed, and every internal
+    /// run of whitespace treated as one space.
+    fn text(&mut self, s: &str) {
+        let mut first = true;
+        for word in s.split_whitespace() {
+            if !first {
+                self.bytes(b" ");
+            }
+            self.bytes(word.as_bytes());
+            first = false;
+        }
+        self.bytes(&[0xFF]);
+    }
+}
+
+impl Puzzle {
+    /// A fingerprint of the puzzle's content: dimensions, solution (with
+    /// rebus text and circles), clues, and title, author, copyright, and
+    /// notes.
+    ///
+    /// The fill grid, the timer, checksums, the file version, and differences
+    /// in whitespace within text fields are ignored. Two copies of a puzzle
+    /// that differ only in those ways have the same fingerprint.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use puz_parse::Puzzle;
+    ///
+    /// let original = Puzzle::new().grid(["AB", "CD"]).title("Tiny");
+    /// let mut copy = original.clone().title("  Tiny ");
+    /// copy.grid.blank = vec!["A-".into(), "--".into()];
+    /// assert_eq!(original.fingerprint(), copy.fingerprint());
+    ///
+    /// let retitled = original.clone().title("Tinier");
+    /// assert_ne!(original.fingerprint(), retitled.fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash = Fnv(self.solution_fingerprint());
+        let info = &self.info;
+        for text in [&info.title, &info.author, &info.copyright, &info.notes] {
+            hash.text(text);
+        }
+        // The numbered clues, then the file's full clue list, which also
+        // covers any extra clues beyond the grid's slots.
+        for set in [&self.clues.across, &self.clues.down] {
+            for (number, clue) in set.iter() {
+                hash.bytes(&number.to_le_bytes());
+                hash.text(clue);
+            }
+            hash.bytes(&[0xFE]);
+        }
+        for clue in &self.clues.raw {
+            hash.text(clue);
+        }
+        hash.0
+    }
+
+    /// A fingerprint of the grid alone: dimensions, the solution, rebus text,
+    /// and circles.
+    ///
+    /// Puzzles with the same solution fingerprint but different
+    /// [`fingerprint`](Self::fingerprint)s are near-duplicates: the same grid
+    /// with edited clues or metadata.
+    pub fn solution_fingerprint(&self) -> u64 {
+        let mut hash = Fnv::new();
+        hash.bytes(&[self.info.width, self.info.height]);
+        for row in &self.grid.solution {
+            hash.field(row);
+        }
+
+        if let Some(rebus) = &self.extensions.rebus {
+            for (r, row) in rebus.grid.iter().enumerate() {
+                for c in 0..row.len() {
+                    if let Some(text) = rebus.get(r, c) {
+                        hash.bytes(&(r as u64).to_le_bytes());
+                        hash.bytes(&(c as u64).to_le_bytes());
+                        hash.field(text);
+                    }
+                }
+            }
+        }
+        hash.bytes(&[0xFE]);
+
+        if let Some(circles) = &self.extensions.circles {
+            for (r, row) in circles.iter().enumerate() {
+                for (c, &circled) in row.iter().enumerate() {
+                    if circled {
+                        hash.bytes(&(r as u64).to_le_bytes());
+                        hash.bytes(&(c as u64).to_le_bytes());
+                    }
+                }
+            }
+        }
+        hash.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Puzzle, Rebus, Timer};
+    use std::collections::HashMap;
+
+    fn sample() -> Puzzle {
+        Puzzle::new()
+            .grid(["CAT", "A.O", "BEE"])
+            .title("Sample")
+            .author("Someone")
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        // Pinned so an accidental change to the hashed fields or their order
+        // shows up here rather than as mismatches against stored values.
+        assert_eq!(sample().fingerprint(), 0x5ef3_d97c_6770_d38d);
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_fill_timer_and_whitespace() {
+        let mut copy = sample().title(" Sample  ").author("Someone\n");
+        copy.grid.blank[0] = "CA-".into();
+        copy.extensions.timer = Some(Timer {
+            elapsed_secs: 90,
+            running: false,
+        });
+        copy.info.version = "1.4".into();
+        assert_eq!(sample().fingerprint(), copy.fingerprint());
+    }
+
+    #[test]
+    fn test_edited_clue_changes_only_the_full_fingerprint() {
+        let original = sample();
+        let mut edited = sample();
+        edited.clues.across.set(1, "Feline");
+        assert_ne!(original.fingerprint(), edited.fingerprint());
+        assert_eq!(
+            original.solution_fingerprint(),
+            edited.solution_fingerprint()
+        );
+    }
+
+    #[test]
+    fn test_solution_fingerprint_covers_rebus_and_circles() {
+        let base = sample();
+
+        let mut rebus = sample();
+        let mut grid = vec![vec![0u8; 3]; 3];
+        grid[0][0] = 1;
+        rebus.extensions.rebus = Some(Rebus {
+            grid,
+            table: HashMap::from([(0, "CAT".to_string())]),
+        });
+        assert_ne!(base.solution_fingerprint(), rebus.solution_fingerprint());
+
+        let mut circled = sample();
+        let mut circles = vec![vec![false; 3]; 3];
+        circles[2][2] = true;
+        circled.extensions.circles = Some(circles);
+        assert_ne!(base.solution_fingerprint(), circled.solution_fingerprint());
+    }
+}
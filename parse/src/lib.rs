@@ -55,8 +55,15 @@
 //!
 //! [`Puzzle::from_file`] and friends record checksum mismatches as
 //! [`PuzWarning::ChecksumMismatch`] and continue (many real-world files have
-//! incorrect checksums). Use `Puzzle::reader().strict(true)` or
-//! [`validate_bytes`] to treat a checksum mismatch as an error instead.
+//! incorrect checksums). Each extension section carries its own checksum too;
+//! a mismatch there is a [`PuzWarning::SectionChecksumMismatch`]. Use
+//! `Puzzle::reader().strict(true)` or [`validate_bytes`] to treat either kind
+//! of mismatch as an error instead.
+//!
+//! [`ParseOptions`] sets the policy for each kind of problem separately:
+//! checksums, extra clues, black squares the two grids disagree on, unreadable
+//! extension sections, string encoding, and a maximum file size. Pass it to
+//! [`PuzzleReader::options`].
 //!
 //! # Building
 //!
@@ -101,16 +108,25 @@
 //! - `json`: Enables JSON serialization support via serde
 
 mod checksums;
-mod encoding;
+mod diff;
+pub mod encoding;
 mod error;
+mod fingerprint;
 mod grid;
+mod options;
 mod parser;
 mod puzzle;
 pub mod raw;
 mod types;
 mod writer;
 
+pub use diff::{
+    CellChange, Change, ClueChange, Extension, ExtensionChange, FieldChange, GridKind, PuzzleDiff,
+};
 pub use error::{ParseResult, PuzError, PuzWarning};
+pub use options::{
+    BlackSquarePolicy, ChecksumPolicy, ExtensionPolicy, ExtraCluePolicy, ParseOptions, TextEncoding,
+};
 pub use puzzle::{Puzzle, PuzzleReader};
 pub use types::*;
 
@@ -146,7 +162,7 @@ use std::path::Path;
 /// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
 pub fn parse<R: Read>(reader: R) -> Result<ParseResult<Puzzle>, PuzError> {
-    parser::parse_puzzle(reader)
+    parser::parse_puzzle(reader, &ParseOptions::new())
 }
 
 /// Parse a .puz file, requiring all stored checksums to match.
@@ -156,7 +172,7 @@ pub fn parse<R: Read>(reader: R) -> Result<ParseResult<Puzzle>, PuzError> {
 /// [`PuzError::InvalidChecksum`] on the first mismatch. Use this when you need
 /// to reject files whose integrity checks fail.
 pub fn parse_strict<R: Read>(reader: R) -> Result<ParseResult<Puzzle>, PuzError> {
-    parser::parse_puzzle_strict(reader)
+    parser::parse_puzzle(reader, &ParseOptions::strict())
 }
 
 /// Validate the checksums of a .puz file without returning the puzzle.
@@ -178,7 +194,7 @@ pub fn parse_strict<R: Read>(reader: R) -> Result<ParseResult<Puzzle>, PuzError>
 /// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
 pub fn validate_bytes(data: &[u8]) -> Result<(), PuzError> {
-    parser::parse_puzzle_strict(data).map(|_| ())
+    parser::parse_puzzle(data, &ParseOptions::strict()).map(|_| ())
 }
 
 /// Parse a .puz file from a file path.
@@ -260,6 +276,31 @@ pub fn to_bytes(puzzle: &Puzzle) -> Result<Vec<u8>, PuzError> {
     writer::write_puzzle(puzzle)
 }
 
+/// Serialize a puzzle as a replacement for `original`, the `.puz` bytes it was
+/// parsed from.
+///
+/// Unlike [`to_bytes`], this keeps what the [`Puzzle`] model can't hold: the
+/// sections after the strings (RUSR, MARK, unknown tags) are copied from
+/// `original`, and so is any modeled section the puzzle didn't change. Use it
+/// to save edits over an existing file. A puzzle with clue strings beyond its
+/// grid's entries can't be written back faithfully, so that is an
+/// [`PuzError::UnsupportedFeature`] error.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use puz_parse::Puzzle;
+///
+/// let original = std::fs::read("puzzle.puz")?;
+/// let mut puzzle = Puzzle::from_bytes(&original)?;
+/// puzzle.info.title = "Retitled".to_string();
+/// std::fs::write("puzzle.puz", puz_parse::rewrite(&original, &puzzle)?)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn rewrite(original: &[u8], puzzle: &Puzzle) -> Result<Vec<u8>, PuzError> {
+    writer::rewrite_puzzle(original, puzzle)
+}
+
 /// Write a puzzle to any type that implements `Write`.
 pub fn write<W: std::io::Write>(puzzle: &Puzzle, mut writer: W) -> Result<(), PuzError> {
     let bytes = to_bytes(puzzle)?;
@@ -0,0 +1,177 @@
+//! How tolerant a parse is, set per concern.
+//!
+//! [`ParseOptions`] is passed to [`PuzzleReader::options`]. Each switch covers
+//! one kind of problem real files have, so a pipeline can, say, reject bad
+//! checksums while still accepting puzzles with extra clues. The defaults are
+//! the lenient parse [`Puzzle::from_file`] has always done.
+//!
+//! [`PuzzleReader::options`]: crate::PuzzleReader::options
+//! [`Puzzle::from_file`]: crate::Puzzle::from_file
+
+/// What to do when a stored checksum doesn't match the data it covers. This
+/// applies to the file's global, CIB, and masked checksums and to each
+/// extension section's checksum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChecksumPolicy {
+    /// Don't verify checksums at all.
+    Ignore,
+    /// Record a [`PuzWarning::ChecksumMismatch`] or
+    /// [`PuzWarning::SectionChecksumMismatch`] and continue.
+    ///
+    /// [`PuzWarning::ChecksumMismatch`]: crate::PuzWarning::ChecksumMismatch
+    /// [`PuzWarning::SectionChecksumMismatch`]: crate::PuzWarning::SectionChecksumMismatch
+    #[default]
+    Warn,
+    /// Fail with [`PuzError::InvalidChecksum`](crate::PuzError::InvalidChecksum).
+    Error,
+}
+
+/// What to do when the file has more clue strings than the grid has entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExtraCluePolicy {
+    /// Keep the extra strings in [`Clues::raw`](crate::Clues::raw) and record
+    /// a [`PuzWarning::ExtraClues`](crate::PuzWarning::ExtraClues).
+    #[default]
+    Warn,
+    /// Fail with [`PuzError::InvalidClueCount`](crate::PuzError::InvalidClueCount).
+    Error,
+    /// Drop the extra strings from [`Clues::raw`](crate::Clues::raw) without
+    /// a warning.
+    Truncate,
+}
+
+/// What to do when the blank grid marks a square black but the solution grid
+/// has something else there.
+///
+/// The reverse, a `.` in the solution where the blank grid is open, is never
+/// a mismatch: it is an answer that contains a period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlackSquarePolicy {
+    /// Fail with [`PuzError::InvalidGrid`](crate::PuzError::InvalidGrid).
+    #[default]
+    Error,
+    /// Trust the solution: open the square in the blank grid. The clues are
+    /// renumbered to match, and words the change creates get empty clues.
+    PreferSolution,
+    /// Trust the blank grid: make the square black in the solution too.
+    PreferBlank,
+}
+
+/// What to do with an extension section (GRBS, RTBL, GEXT, LTIM) that can't
+/// be read, such as one whose size doesn't match the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExtensionPolicy {
+    /// Leave the extension out and record a
+    /// [`PuzWarning::SkippedExtension`](crate::PuzWarning::SkippedExtension).
+    #[default]
+    Skip,
+    /// Fail with the reason the section was unreadable.
+    Error,
+}
+
+/// How the file's strings (title, author, clues, notes, rebus text) are
+/// decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextEncoding {
+    /// UTF-8 when a string is valid UTF-8, otherwise Windows-1252, as in
+    /// [`decode_puz_string`](crate::encoding::decode_puz_string).
+    #[default]
+    Auto,
+    /// Always Windows-1252, the format's original encoding. Use this for
+    /// legacy files whose bytes happen to also be valid UTF-8.
+    Windows1252,
+    /// Require UTF-8; any other string fails with
+    /// [`PuzError::InvalidUtf8`](crate::PuzError::InvalidUtf8).
+    Utf8,
+}
+
+/// Settings for [`PuzzleReader`](crate::PuzzleReader), one per kind of
+/// problem a file can have.
+///
+/// Start from [`ParseOptions::new`] (the lenient defaults) or
+/// [`ParseOptions::strict`] and chain setters:
+///
+/// ```
+/// use puz_parse::{ChecksumPolicy, ExtraCluePolicy, ParseOptions, Puzzle};
+///
+/// let options = ParseOptions::new()
+///     .checksums(ChecksumPolicy::Error)
+///     .extra_clues(ExtraCluePolicy::Truncate)
+///     .max_file_size(1 << 20);
+///
+/// let bytes = puz_parse::to_bytes(&Puzzle::new().grid(["AB", "CD"]))?;
+/// let puzzle = Puzzle::reader().options(options).from_bytes(&bytes)?;
+/// assert_eq!(puzzle.info.width, 2);
+/// # Ok::<(), puz_parse::PuzError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ParseOptions {
+    pub(crate) checksums: ChecksumPolicy,
+    pub(crate) extra_clues: ExtraCluePolicy,
+    pub(crate) black_squares: BlackSquarePolicy,
+    pub(crate) extensions: ExtensionPolicy,
+    pub(crate) encoding: TextEncoding,
+    pub(crate) max_file_size: Option<u64>,
+}
+
+impl ParseOptions {
+    /// The lenient defaults: checksum mismatches, extra clues, and unreadable
+    /// extensions are warnings; a black-square mismatch is an error; strings
+    /// are decoded as UTF-8 or Windows-1252; any file size is accepted.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The defaults, except that any checksum mismatch is an error. This is
+    /// what [`PuzzleReader::strict`](crate::PuzzleReader::strict) and
+    /// [`parse_strict`](crate::parse_strict) use.
+    #[must_use]
+    pub fn strict() -> Self {
+        Self::default().checksums(ChecksumPolicy::Error)
+    }
+
+    /// Set the [`ChecksumPolicy`].
+    #[must_use]
+    pub fn checksums(mut self, policy: ChecksumPolicy) -> Self {
+        self.checksums = policy;
+        self
+    }
+
+    /// Set the [`ExtraCluePolicy`].
+    #[must_use]
+    pub fn extra_clues(mut self, policy: ExtraCluePolicy) -> Self {
+        self.extra_clues = policy;
+        self
+    }
+
+    /// Set the [`BlackSquarePolicy`].
+    #[must_use]
+    pub fn black_squares(mut self, policy: BlackSquarePolicy) -> Self {
+        self.black_squares = policy;
+        self
+    }
+
+    /// Set the [`ExtensionPolicy`].
+    #[must_use]
+    pub fn extensions(mut self, policy: ExtensionPolicy) -> Self {
+        self.extensions = policy;
+        self
+    }
+
+    /// Set the [`TextEncoding`].
+    #[must_use]
+    pub fn encoding(mut self, encoding: TextEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Reject input longer than `bytes` with
+    /// [`PuzError::FileTooLarge`](crate::PuzError::FileTooLarge). At most
+    /// `bytes + 1` bytes are read, so an oversized stream is never buffered.
+    #[must_use]
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     error::{PuzError, PuzWarning},
     grid::{cell_needs_across_clue, cell_needs_down_clue},
+    options::ExtraCluePolicy,
     types::Clues,
 };
 
@@ -11,12 +12,14 @@ use crate::{
 ///
 /// - Fewer clue strings than grid slots is a hard error: the grid cannot be
 ///   clued and there is no meaningful mapping.
-/// - More clue strings than grid slots is tolerated: the extra strings stay in
-///   `raw` and a [`PuzWarning::ExtraClues`] is returned. Some puzzles author
-///   extra clues (for example a meta-puzzle revealer) with no grid slot.
+/// - More clue strings than grid slots is handled by `extra`. By default the
+///   extra strings stay in `raw` and a [`PuzWarning::ExtraClues`] is returned.
+///   Some puzzles author extra clues (for example a meta-puzzle revealer) with
+///   no grid slot.
 pub(crate) fn process_clues(
     blank_grid: &[String],
     clue_strings: &[String],
+    extra: ExtraCluePolicy,
 ) -> Result<(Clues, Option<PuzWarning>), PuzError> {
     let mut clues = Clues::default();
     let mut clue_index = 0;
@@ -69,19 +72,142 @@ pub(crate) fn process_clues(
     // Preserve the complete clue list from the file, in order, with no loss.
     clues.raw = clue_strings.to_vec();
 
-    // Extra clue strings beyond the grid's slots are tolerated: the file stored
-    // more clues than the grid can number (e.g. a meta revealer). Keep them in
+    // Extra clue strings beyond the grid's slots: the file stored more clues
+    // than the grid can number (e.g. a meta revealer). By default keep them in
     // `raw` and report a recoverable warning.
-    let warning = if clue_index < clue_strings.len() {
-        Some(PuzWarning::ExtraClues {
-            slots: clue_index,
-            provided: clue_strings.len(),
-        })
-    } else {
-        None
+    if clue_index == clue_strings.len() {
+        return Ok((clues, None));
+    }
+    match extra {
+        ExtraCluePolicy::Warn => Ok((
+            clues,
+            Some(PuzWarning::ExtraClues {
+                slots: clue_index,
+                provided: clue_strings.len(),
+            }),
+        )),
+        ExtraCluePolicy::Error => Err(PuzError::InvalidClueCount {
+            expected: clue_index as u16,
+            found: clue_strings.len(),
+        }),
+        ExtraCluePolicy::Truncate => {
+            clues.raw.truncate(clue_index);
+            Ok((clues, None))
+        }
+    }
+}
+
+/// Move clues numbered on the `stored` blank grid onto `repaired`, the same
+/// grid after [`BlackSquarePolicy::PreferSolution`] opened some squares.
+///
+/// Opening a square can start new words and merge or remove old ones, which
+/// shifts the numbering. A word in `repaired` keeps the clue of the stored word
+/// that starts on the same square in the same direction; a word with no such
+/// counterpart gets an empty clue. Clues whose word no longer starts anywhere
+/// are dropped. Each added or dropped clue is recorded as a
+/// [`PuzWarning::DataRecovery`]. [`Clues::raw`] is left as the file stored it.
+///
+/// [`BlackSquarePolicy::PreferSolution`]: crate::BlackSquarePolicy::PreferSolution
+pub(crate) fn renumber_clues(
+    stored: &[String],
+    repaired: &[String],
+    clues: Clues,
+) -> (Clues, Vec<PuzWarning>) {
+    let before = word_starts(stored);
+    let after = word_starts(repaired);
+    let mut renumbered = Clues {
+        raw: clues.raw.clone(),
+        ..Clues::default()
     };
+    let mut warnings = Vec::new();
+    let mut recover = |issue: String| {
+        warnings.push(PuzWarning::DataRecovery {
+            field: "clues".to_string(),
+            issue,
+        });
+    };
+
+    for (direction, old, new) in [
+        ("across", &clues.across, &mut renumbered.across),
+        ("down", &clues.down, &mut renumbered.down),
+    ] {
+        let start_of = |words: &[WordStart], number| {
+            words
+                .iter()
+                .find(|w| w.number == number && w.starts(direction))
+                .map(|w| (w.row, w.col))
+        };
+        let number_at = |words: &[WordStart], cell| {
+            words
+                .iter()
+                .find(|w| (w.row, w.col) == cell && w.starts(direction))
+                .map(|w| w.number)
+        };
+        for word in after.iter().filter(|w| w.starts(direction)) {
+            let text = number_at(&before, (word.row, word.col)).and_then(|n| old.get(n));
+            if text.is_none() {
+                recover(format!(
+                    "{} {direction} at ({}, {}) is a new word with no clue",
+                    word.number, word.row, word.col
+                ));
+            }
+            new.set(word.number, text.unwrap_or_default());
+        }
+        for (number, text) in old.iter() {
+            let Some(cell) = start_of(&before, number) else {
+                continue;
+            };
+            if number_at(&after, cell).is_none() {
+                recover(format!(
+                    "dropped the clue for {number} {direction} (\"{text}\"); its word no longer starts at ({}, {})",
+                    cell.0, cell.1
+                ));
+            }
+        }
+    }
+    (renumbered, warnings)
+}
+
+/// A numbered square and the words that start on it.
+struct WordStart {
+    row: usize,
+    col: usize,
+    number: u16,
+    across: bool,
+    down: bool,
+}
+
+impl WordStart {
+    fn starts(&self, direction: &str) -> bool {
+        if direction == "across" {
+            self.across
+        } else {
+            self.down
+        }
+    }
+}
 
-    Ok((clues, warning))
+/// The numbered squares of a blank grid in reading order.
+fn word_starts(grid: &[String]) -> Vec<WordStart> {
+    let mut starts = Vec::new();
+    let mut number = 1u16;
+    for row in 0..grid.len() {
+        for col in 0..grid[row].chars().count() {
+            let across = cell_needs_across_clue(grid, row, col);
+            let down = cell_needs_down_clue(grid, row, col);
+            if across || down {
+                starts.push(WordStart {
+                    row,
+                    col,
+                    number,
+                    across,
+                    down,
+                });
+                number += 1;
+            }
+        }
+    }
+    starts
 }
 
 #[cfg(test)]
@@ -97,7 +223,7 @@ mod tests {
     #[test]
     fn test_exact_clue_count_maps_and_no_warning() {
         let clues = ["1A", "1D", "2D", "3A"].map(String::from);
-        let (result, warning) = process_clues(&open_2x2(), &clues).unwrap();
+        let (result, warning) = process_clues(&open_2x2(), &clues, ExtraCluePolicy::Warn).unwrap();
         assert!(warning.is_none());
         assert_eq!(result.across.get(1), Some("1A"));
         assert_eq!(result.across.get(3), Some("3A"));
@@ -110,7 +236,7 @@ mod tests {
     fn test_extra_clues_warn_and_preserve_raw() {
         // 5 clue strings for a 4-slot grid: the extra is tolerated.
         let clues = ["1A", "1D", "2D", "3A", "EXTRA"].map(String::from);
-        let (result, warning) = process_clues(&open_2x2(), &clues).unwrap();
+        let (result, warning) = process_clues(&open_2x2(), &clues, ExtraCluePolicy::Warn).unwrap();
 
         assert_eq!(
             warning,
@@ -131,7 +257,49 @@ mod tests {
     fn test_too_few_clues_is_an_error() {
         // Only 3 clue strings for a 4-slot grid: cannot number the grid.
         let clues = ["1A", "1D", "2D"].map(String::from);
-        let err = process_clues(&open_2x2(), &clues).unwrap_err();
+        let err = process_clues(&open_2x2(), &clues, ExtraCluePolicy::Warn).unwrap_err();
         assert!(matches!(err, PuzError::InvalidClues { .. }));
     }
+
+    #[test]
+    fn test_extra_clues_error_or_truncate_by_policy() {
+        let clues = ["1A", "1D", "2D", "3A", "EXTRA"].map(String::from);
+
+        let err = process_clues(&open_2x2(), &clues, ExtraCluePolicy::Error).unwrap_err();
+        assert_eq!(
+            err,
+            PuzError::InvalidClueCount {
+                expected: 4,
+                found: 5,
+            }
+        );
+
+        let (result, warning) =
+            process_clues(&open_2x2(), &clues, ExtraCluePolicy::Truncate).unwrap();
+        assert!(warning.is_none());
+        assert_eq!(result.raw, clues[..4].to_vec());
+        assert_eq!(result.across.get(3), Some("3A"));
+    }
+
+    #[test]
+    fn test_renumber_drops_clues_of_merged_words() {
+        // Opening the middle square joins 1A and 2A into one word.
+        let stored = vec!["--.--".to_string()];
+        let repaired = vec!["-----".to_string()];
+        let (clues, _) = process_clues(
+            &stored,
+            &["one", "two"].map(String::from),
+            ExtraCluePolicy::Warn,
+        )
+        .unwrap();
+
+        let (clues, warnings) = renumber_clues(&stored, &repaired, clues);
+        assert_eq!(clues.across.get(1), Some("one"));
+        assert_eq!(clues.across.len(), 1);
+        assert_eq!(clues.raw, vec!["one", "two"]);
+        assert!(matches!(
+            &warnings[..],
+            [PuzWarning::DataRecovery { issue, .. }] if issue.contains("\"two\"")
+        ));
+    }
 }
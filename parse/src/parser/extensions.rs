@@ -1,7 +1,9 @@
 use super::io::find_section;
 use crate::{
+    checksums::cksum_region,
     error::{PuzError, PuzWarning},
-    types::{Extensions, Rebus},
+    options::{ChecksumPolicy, ExtensionPolicy, ParseOptions, TextEncoding},
+    types::{Extensions, Rebus, Timer},
 };
 use std::collections::HashMap;
 
@@ -10,40 +12,70 @@ use std::collections::HashMap;
 enum ExtraSection {
     GRBS,
     RTBL,
+    LTIM,
     GEXT,
 }
 
-const EXTRA_SECTIONS: [(&str, ExtraSection); 3] = [
+const EXTRA_SECTIONS: [(&str, ExtraSection); 4] = [
     ("GRBS", ExtraSection::GRBS),
     ("RTBL", ExtraSection::RTBL),
+    ("LTIM", ExtraSection::LTIM),
     ("GEXT", ExtraSection::GEXT),
 ];
 
+/// Parse the extension sections after the strings. Sections that can't be
+/// used, and sections whose stored checksum doesn't match their data, are
+/// warnings or errors as `options` says.
 pub(crate) fn parse_extensions_with_recovery(
     data: &[u8],
     width: u8,
     height: u8,
+    options: &ParseOptions,
 ) -> Result<(Extensions, Vec<PuzWarning>), PuzError> {
     let mut rebus = None;
     let mut circles = None;
     let mut given = None;
+    let mut timer = None;
     let mut warnings = Vec::new();
 
     for (section_name, section_type) in &EXTRA_SECTIONS {
         match find_section(data, section_name) {
-            Ok(Some(section_data)) => {
+            Ok(Some(section)) => {
+                let computed = cksum_region(&section.data, 0);
+                if computed != section.checksum {
+                    match options.checksums {
+                        ChecksumPolicy::Ignore => {}
+                        ChecksumPolicy::Warn => {
+                            warnings.push(PuzWarning::SectionChecksumMismatch {
+                                section: section_name.to_string(),
+                                expected: computed,
+                                found: section.checksum,
+                            });
+                        }
+                        ChecksumPolicy::Error => {
+                            return Err(PuzError::InvalidChecksum {
+                                expected: computed,
+                                found: section.checksum,
+                                context: format!("{section_name} section"),
+                            });
+                        }
+                    }
+                }
+                let section_data = section.data;
                 match section_type {
                     ExtraSection::GRBS => {
                         let expected_size = (width as usize) * (height as usize);
                         if section_data.len() != expected_size {
-                            warnings.push(PuzWarning::SkippedExtension {
-                                section: "GRBS".to_string(),
-                                reason: format!(
+                            skip(
+                                &mut warnings,
+                                options.extensions,
+                                "GRBS",
+                                format!(
                                     "Size mismatch: expected {} bytes, got {}",
                                     expected_size,
                                     section_data.len()
                                 ),
-                            });
+                            )?;
                             continue;
                         }
 
@@ -57,60 +89,86 @@ pub(crate) fn parse_extensions_with_recovery(
                         }
 
                         match find_section(data, "RTBL") {
-                            Ok(Some(rtbl_data)) => {
-                                match parse_rebus(&section_data, &rtbl_data, width, height) {
+                            Ok(Some(rtbl)) => {
+                                match parse_rebus(
+                                    &section_data,
+                                    &rtbl.data,
+                                    width,
+                                    height,
+                                    options.encoding,
+                                ) {
                                     Ok(parsed_rebus) => rebus = Some(parsed_rebus),
-                                    Err(e) => warnings.push(PuzWarning::SkippedExtension {
-                                        section: "GRBS/RTBL".to_string(),
-                                        reason: format!("Failed to parse rebus data: {e}"),
-                                    }),
+                                    Err(e) => skip(
+                                        &mut warnings,
+                                        options.extensions,
+                                        "GRBS/RTBL",
+                                        format!("Failed to parse rebus data: {e}"),
+                                    )?,
                                 }
                             }
-                            Ok(None) => warnings.push(PuzWarning::SkippedExtension {
-                                section: "GRBS".to_string(),
-                                reason:
-                                    "RTBL section not found - rebus requires both GRBS and RTBL"
-                                        .to_string(),
-                            }),
-                            Err(e) => warnings.push(PuzWarning::SkippedExtension {
-                                section: "GRBS".to_string(),
-                                reason: format!("Failed to read RTBL section: {e}"),
-                            }),
+                            Ok(None) => skip(
+                                &mut warnings,
+                                options.extensions,
+                                "GRBS",
+                                "RTBL section not found - rebus requires both GRBS and RTBL"
+                                    .to_string(),
+                            )?,
+                            Err(e) => skip(
+                                &mut warnings,
+                                options.extensions,
+                                "GRBS",
+                                format!("Failed to read RTBL section: {e}"),
+                            )?,
                         }
                     }
                     ExtraSection::GEXT => {
                         // Validate GEXT section size first
                         let expected_size = (width as usize) * (height as usize);
                         if section_data.len() != expected_size {
-                            warnings.push(PuzWarning::SkippedExtension {
-                                section: "GEXT".to_string(),
-                                reason: format!(
+                            skip(
+                                &mut warnings,
+                                options.extensions,
+                                "GEXT",
+                                format!(
                                     "Size mismatch: expected {} bytes, got {}",
                                     expected_size,
                                     section_data.len()
                                 ),
-                            });
+                            )?;
                         } else {
                             match parse_gext(&section_data, width, height) {
                                 Ok((parsed_circles, parsed_given)) => {
                                     circles = parsed_circles;
                                     given = parsed_given;
                                 }
-                                Err(e) => warnings.push(PuzWarning::SkippedExtension {
-                                    section: "GEXT".to_string(),
-                                    reason: format!("Failed to parse GEXT data: {e}"),
-                                }),
+                                Err(e) => skip(
+                                    &mut warnings,
+                                    options.extensions,
+                                    "GEXT",
+                                    format!("Failed to parse GEXT data: {e}"),
+                                )?,
                             }
                         }
                     }
+                    ExtraSection::LTIM => match parse_ltim(&section_data) {
+                        Ok(parsed_timer) => timer = Some(parsed_timer),
+                        Err(e) => skip(
+                            &mut warnings,
+                            options.extensions,
+                            "LTIM",
+                            format!("Failed to parse timer data: {e}"),
+                        )?,
+                    },
                     ExtraSection::RTBL => {}
                 }
             }
             Ok(None) => {}
-            Err(e) => warnings.push(PuzWarning::SkippedExtension {
-                section: section_name.to_string(),
-                reason: format!("Failed to read section: {e}"),
-            }),
+            Err(e) => skip(
+                &mut warnings,
+                options.extensions,
+                section_name,
+                format!("Failed to read section: {e}"),
+            )?,
         }
     }
 
@@ -119,16 +177,42 @@ pub(crate) fn parse_extensions_with_recovery(
             rebus,
             circles,
             given,
+            timer,
         },
         warnings,
     ))
 }
 
+/// Record a section that can't be used as skipped, or fail under
+/// [`ExtensionPolicy::Error`].
+fn skip(
+    warnings: &mut Vec<PuzWarning>,
+    policy: ExtensionPolicy,
+    section: &str,
+    reason: String,
+) -> Result<(), PuzError> {
+    match policy {
+        ExtensionPolicy::Skip => {
+            warnings.push(PuzWarning::SkippedExtension {
+                section: section.to_string(),
+                reason,
+            });
+            Ok(())
+        }
+        ExtensionPolicy::Error => Err(PuzError::ParseError {
+            message: reason,
+            position: None,
+            context: section.to_string(),
+        }),
+    }
+}
+
 fn parse_rebus(
     grbs_data: &[u8],
     rtbl_data: &[u8],
     width: u8,
     height: u8,
+    encoding: TextEncoding,
 ) -> Result<Rebus, PuzError> {
     // Rebus data format:
     // See: https://github.com/mwln/puz.rs/blob/main/PUZ.md
@@ -156,7 +240,7 @@ fn parse_rebus(
         .collect();
 
     // Parse RTBL table: decode string and split on semicolons
-    let rtbl_str = crate::encoding::decode_puz_string(rtbl_data)?;
+    let rtbl_str = crate::encoding::decode_with(rtbl_data, encoding)?;
     let mut table = HashMap::new();
 
     for entry in rtbl_str.split(';') {
@@ -175,6 +259,39 @@ fn parse_rebus(
     Ok(Rebus { grid, table })
 }
 
+fn parse_ltim(data: &[u8]) -> Result<Timer, PuzError> {
+    // LTIM section format:
+    // See: https://github.com/mwln/puz.rs/blob/main/PUZ.md
+    //
+    // ASCII "<elapsed seconds>,<state>", e.g. "42,1"
+    // - state 0: the timer was running when saved
+    // - state 1: the timer was stopped
+
+    let invalid = |reason: &str| PuzError::ParseError {
+        message: reason.to_string(),
+        position: None,
+        context: "LTIM".to_string(),
+    };
+    let text = std::str::from_utf8(data).map_err(|_| invalid("timer is not ASCII"))?;
+    let (elapsed, state) = text
+        .trim_end_matches('\0')
+        .split_once(',')
+        .ok_or_else(|| invalid("expected \"<seconds>,<state>\""))?;
+    let elapsed_secs = elapsed
+        .trim()
+        .parse()
+        .map_err(|_| invalid("elapsed time is not a number"))?;
+    let running = match state.trim() {
+        "0" => true,
+        "1" => false,
+        _ => return Err(invalid("timer state is not 0 or 1")),
+    };
+    Ok(Timer {
+        elapsed_secs,
+        running,
+    })
+}
+
 type GextResult = (Option<Vec<Vec<bool>>>, Option<Vec<Vec<bool>>>);
 
 fn parse_gext(data: &[u8], width: u8, height: u8) -> Result<GextResult, PuzError> {
@@ -229,12 +346,12 @@ mod tests {
     use super::*;
 
     /// Frame a section as it appears on disk: 4-byte tag, u16 LE length, u16
-    /// checksum (unused by the parser), then the data bytes.
+    /// data checksum, then the data bytes.
     fn section(tag: &str, data: &[u8]) -> Vec<u8> {
         let mut out = Vec::new();
         out.extend_from_slice(tag.as_bytes());
         out.extend_from_slice(&(data.len() as u16).to_le_bytes());
-        out.extend_from_slice(&[0, 0]); // checksum, ignored on read
+        out.extend_from_slice(&cksum_region(data, 0).to_le_bytes());
         out.extend_from_slice(data);
         out
     }
@@ -245,7 +362,8 @@ mod tests {
         // needed. This must not warn. (Matches ~60 real NYT files.)
         let (w, h) = (2u8, 2u8);
         let grbs = section("GRBS", &[0, 0, 0, 0]);
-        let (ext, warnings) = parse_extensions_with_recovery(&grbs, w, h).unwrap();
+        let (ext, warnings) =
+            parse_extensions_with_recovery(&grbs, w, h, &ParseOptions::new()).unwrap();
         assert!(ext.rebus.is_none());
         assert!(
             warnings.is_empty(),
@@ -258,7 +376,8 @@ mod tests {
         // A GRBS that marks a cell but has no RTBL is a genuinely broken rebus.
         let (w, h) = (2u8, 2u8);
         let grbs = section("GRBS", &[1, 0, 0, 0]); // cell (0,0) uses rebus key 1
-        let (ext, warnings) = parse_extensions_with_recovery(&grbs, w, h).unwrap();
+        let (ext, warnings) =
+            parse_extensions_with_recovery(&grbs, w, h, &ParseOptions::new()).unwrap();
         assert!(ext.rebus.is_none());
         assert_eq!(warnings.len(), 1);
         assert!(matches!(
@@ -272,9 +391,79 @@ mod tests {
         let (w, h) = (2u8, 2u8);
         let mut data = section("GRBS", &[1, 0, 0, 0]);
         data.extend(section("RTBL", b" 1:HEART;"));
-        let (ext, warnings) = parse_extensions_with_recovery(&data, w, h).unwrap();
+        let (ext, warnings) =
+            parse_extensions_with_recovery(&data, w, h, &ParseOptions::new()).unwrap();
         let rebus = ext.rebus.expect("rebus should parse");
         assert_eq!(rebus.table.get(&1).map(String::as_str), Some("HEART"));
         assert!(warnings.is_empty(), "got: {warnings:?}");
     }
+
+    #[test]
+    fn test_ltim_parses_timer() {
+        let data = section("LTIM", b"754,1");
+        let (ext, warnings) =
+            parse_extensions_with_recovery(&data, 2, 2, &ParseOptions::new()).unwrap();
+        assert_eq!(
+            ext.timer,
+            Some(Timer {
+                elapsed_secs: 754,
+                running: false,
+            })
+        );
+        assert!(warnings.is_empty(), "got: {warnings:?}");
+
+        let data = section("LTIM", b"0,0");
+        let (ext, _) = parse_extensions_with_recovery(&data, 2, 2, &ParseOptions::new()).unwrap();
+        assert_eq!(ext.timer.map(|t| t.running), Some(true));
+    }
+
+    #[test]
+    fn test_malformed_ltim_warns() {
+        let data = section("LTIM", b"soon");
+        let (ext, warnings) =
+            parse_extensions_with_recovery(&data, 2, 2, &ParseOptions::new()).unwrap();
+        assert!(ext.timer.is_none());
+        assert!(matches!(
+            &warnings[..],
+            [PuzWarning::SkippedExtension { section, .. }] if section == "LTIM"
+        ));
+    }
+
+    #[test]
+    fn test_section_checksum_mismatch_warns_and_still_reads() {
+        let mut data = section("GEXT", &[0x80, 0, 0, 0]);
+        data[6] ^= 0xFF; // corrupt the stored checksum
+        let (ext, warnings) =
+            parse_extensions_with_recovery(&data, 2, 2, &ParseOptions::new()).unwrap();
+        assert!(ext.circles.is_some());
+        assert!(matches!(
+            &warnings[..],
+            [PuzWarning::SectionChecksumMismatch { section, .. }] if section == "GEXT"
+        ));
+    }
+
+    #[test]
+    fn test_section_checksum_mismatch_is_an_error_when_strict() {
+        let mut data = section("GEXT", &[0x80, 0, 0, 0]);
+        data[8] = 0x40; // corrupt the data after the checksum was taken
+        let err = parse_extensions_with_recovery(&data, 2, 2, &ParseOptions::strict()).unwrap_err();
+        assert!(matches!(
+            err,
+            PuzError::InvalidChecksum { context, .. } if context == "GEXT section"
+        ));
+
+        let data = section("GEXT", &[0x80, 0, 0, 0]);
+        assert!(parse_extensions_with_recovery(&data, 2, 2, &ParseOptions::strict()).is_ok());
+    }
+
+    #[test]
+    fn test_unreadable_section_is_an_error_under_extension_policy() {
+        let data = section("GEXT", &[0x80, 0]); // too short for a 2x2 grid
+        let options = ParseOptions::new().extensions(ExtensionPolicy::Error);
+        let err = parse_extensions_with_recovery(&data, 2, 2, &options).unwrap_err();
+        assert!(matches!(
+            err,
+            PuzError::ParseError { context, .. } if context == "GEXT"
+        ));
+    }
 }
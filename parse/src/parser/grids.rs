@@ -1,5 +1,10 @@
 use super::io::read_bytes;
-use crate::{error::PuzError, grid::TAKEN_SQUARE, types::Grid};
+use crate::{
+    error::{PuzError, PuzWarning},
+    grid::{FREE_SQUARE, TAKEN_SQUARE},
+    options::BlackSquarePolicy,
+    types::Grid,
+};
 use std::io::{BufReader, Read};
 
 pub(crate) fn parse_grids<R: Read>(
@@ -57,7 +62,8 @@ pub(crate) fn parse_grids<R: Read>(
     let solution = string_to_grid(&solution_chars, width as usize);
     let blank = string_to_grid(&blank_chars, width as usize);
 
-    // Ensure blocked squares match between grids
+    // Ensure both grids are width x height; black squares are checked by
+    // `resolve_black_squares`, which the caller runs under its policy.
     validate_grid_consistency(&solution, &blank, width, height)?;
 
     Ok((Grid { blank, solution }, is_diagramless))
@@ -101,25 +107,65 @@ fn validate_grid_consistency(
                 ),
             });
         }
+    }
+
+    Ok(())
+}
 
-        for (j, (sol_char, blank_char)) in sol_row.chars().zip(blank_row.chars()).enumerate() {
-            // The blank grid is authoritative for black squares: it is what the
-            // solver sees. Only error when the blank grid marks a cell black but
-            // the solution does not have a black square there — a genuine
-            // inconsistency. The reverse (a '.' in the solution where the blank
-            // is open) is allowed: it is theme/rebus content, such as a literal
-            // period placed in an answer (e.g. NYT punctuation-rebus puzzles).
-            if blank_char == TAKEN_SQUARE && sol_char != TAKEN_SQUARE {
-                return Err(PuzError::InvalidGrid {
-                    reason: format!(
-                        "Grid consistency error at ({i}, {j}): blank grid marks a black square but the solution does not"
-                    ),
-                });
+/// Resolve squares the blank grid marks black but the solution does not, as
+/// `policy` says: fail, or rewrite one grid to agree with the other and warn.
+///
+/// The blank grid is what the solver sees, so only this direction is a
+/// mismatch. The reverse (a '.' in the solution where the blank is open) is
+/// allowed: it is theme/rebus content, such as a literal period placed in an
+/// answer (e.g. NYT punctuation-rebus puzzles).
+pub(crate) fn resolve_black_squares(
+    grid: &mut Grid,
+    policy: BlackSquarePolicy,
+) -> Result<Vec<PuzWarning>, PuzError> {
+    let mut warnings = Vec::new();
+    for (i, (sol_row, blank_row)) in grid.solution.iter_mut().zip(&mut grid.blank).enumerate() {
+        let mut sol_cells: Vec<char> = sol_row.chars().collect();
+        let mut blank_cells: Vec<char> = blank_row.chars().collect();
+        let mut changed = false;
+        for (j, (sol_char, blank_char)) in sol_cells.iter_mut().zip(&mut blank_cells).enumerate() {
+            if *blank_char != TAKEN_SQUARE || *sol_char == TAKEN_SQUARE {
+                continue;
             }
+            let issue = match policy {
+                BlackSquarePolicy::Error => {
+                    return Err(PuzError::InvalidGrid {
+                        reason: format!(
+                            "Grid consistency error at ({i}, {j}): blank grid marks a black square but the solution does not"
+                        ),
+                    });
+                }
+                BlackSquarePolicy::PreferSolution => {
+                    *blank_char = FREE_SQUARE;
+                    format!(
+                        "blank grid marks ({i}, {j}) black but the solution has '{sol_char}'; opened the square"
+                    )
+                }
+                BlackSquarePolicy::PreferBlank => {
+                    let issue = format!(
+                        "blank grid marks ({i}, {j}) black but the solution has '{sol_char}'; made it black"
+                    );
+                    *sol_char = TAKEN_SQUARE;
+                    issue
+                }
+            };
+            changed = true;
+            warnings.push(PuzWarning::DataRecovery {
+                field: "grid".to_string(),
+                issue,
+            });
+        }
+        if changed {
+            *sol_row = sol_cells.into_iter().collect();
+            *blank_row = blank_cells.into_iter().collect();
         }
     }
-
-    Ok(())
+    Ok(warnings)
 }
 
 #[cfg(test)]
@@ -264,13 +310,48 @@ mod tests {
         data.extend_from_slice(b"ABCD"); // solution: all letters
         data.extend_from_slice(b"-.--"); // blank marks (0,1) black
         let mut reader = BufReader::new(Cursor::new(data));
-        let result = parse_grids(&mut reader, 2, 2);
+        let (mut grid, _) = parse_grids(&mut reader, 2, 2).unwrap();
+        let result = resolve_black_squares(&mut grid, BlackSquarePolicy::Error);
         assert!(
             result.is_err(),
             "blank black + solution letter is inconsistent"
         );
     }
 
+    #[test]
+    fn test_resolve_black_squares_prefers_one_grid() {
+        let mismatched = || Grid {
+            solution: vec!["AB".to_string(), "CD".to_string()],
+            blank: vec!["-.".to_string(), "--".to_string()],
+        };
+
+        let mut grid = mismatched();
+        let warnings = resolve_black_squares(&mut grid, BlackSquarePolicy::PreferSolution).unwrap();
+        assert_eq!(grid.blank, vec!["--".to_string(), "--".to_string()]);
+        assert_eq!(grid.solution, mismatched().solution);
+        assert!(matches!(
+            &warnings[..],
+            [PuzWarning::DataRecovery { field, .. }] if field == "grid"
+        ));
+
+        let mut grid = mismatched();
+        let warnings = resolve_black_squares(&mut grid, BlackSquarePolicy::PreferBlank).unwrap();
+        assert_eq!(grid.solution, vec!["A.".to_string(), "CD".to_string()]);
+        assert_eq!(grid.blank, mismatched().blank);
+        assert_eq!(warnings.len(), 1);
+
+        // A consistent grid is left alone under every policy.
+        let mut grid = Grid {
+            solution: vec!["A.".to_string()],
+            blank: vec!["-.".to_string()],
+        };
+        assert!(
+            resolve_black_squares(&mut grid, BlackSquarePolicy::Error)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     /// Test parsing grids with all black squares
     /// Edge case where entire puzzle is blocked
     #[test]
@@ -1,4 +1,5 @@
 use crate::error::PuzError;
+use crate::options::TextEncoding;
 use byteorder::{ByteOrder, LittleEndian};
 use std::io::{BufReader, Read};
 
@@ -64,6 +65,7 @@ pub(crate) fn read_bytes<R: Read>(
 /// the original bytes.
 pub(crate) fn read_string_until_nul_raw<R: Read>(
     reader: &mut BufReader<R>,
+    encoding: TextEncoding,
 ) -> Result<(String, Vec<u8>), PuzError> {
     let mut bytes = Vec::new();
     loop {
@@ -74,7 +76,7 @@ pub(crate) fn read_string_until_nul_raw<R: Read>(
         }
         bytes.push(byte[0]);
     }
-    let decoded = crate::encoding::decode_puz_string(&bytes)?;
+    let decoded = crate::encoding::decode_with(&bytes, encoding)?;
     Ok((decoded, bytes))
 }
 
@@ -84,7 +86,14 @@ pub(crate) fn read_remaining_data<R: Read>(reader: &mut BufReader<R>) -> Result<
     Ok(data)
 }
 
-pub(crate) fn find_section(data: &[u8], section_name: &str) -> Result<Option<Vec<u8>>, PuzError> {
+/// An extension section found by [`find_section`].
+pub(crate) struct Section {
+    pub(crate) data: Vec<u8>,
+    /// The checksum stored in the section's header.
+    pub(crate) checksum: u16,
+}
+
+pub(crate) fn find_section(data: &[u8], section_name: &str) -> Result<Option<Section>, PuzError> {
     // Extension sections format (after main puzzle data):
     // See: https://github.com/mwln/puz.rs/blob/main/PUZ.md
     //
@@ -105,7 +114,10 @@ pub(crate) fn find_section(data: &[u8], section_name: &str) -> Result<Option<Vec
             let data_start = length_start + 4; // skip length (2) + checksum (2)
             let data_end = data_start + data_length;
             if data_end <= data.len() {
-                return Ok(Some(data[data_start..data_end].to_vec()));
+                return Ok(Some(Section {
+                    data: data[data_start..data_end].to_vec(),
+                    checksum: LittleEndian::read_u16(&data[length_start + 2..data_start]),
+                }));
             }
         }
     }
@@ -247,14 +259,35 @@ mod tests {
         let data = vec![72, 101, 108, 108, 111, 0, 87, 111, 114, 108, 100, 0];
         let mut reader = BufReader::new(Cursor::new(data));
 
-        let (decoded, raw) = read_string_until_nul_raw(&mut reader).unwrap();
+        let (decoded, raw) = read_string_until_nul_raw(&mut reader, TextEncoding::Auto).unwrap();
         assert_eq!(decoded, "Hello");
         assert_eq!(raw, b"Hello");
 
-        let (decoded, _) = read_string_until_nul_raw(&mut reader).unwrap();
+        let (decoded, _) = read_string_until_nul_raw(&mut reader, TextEncoding::Auto).unwrap();
         assert_eq!(decoded, "World");
     }
 
+    #[test]
+    fn test_read_string_until_nul_encodings() {
+        let read = |data: &[u8], encoding| {
+            let mut reader = BufReader::new(Cursor::new(data.to_vec()));
+            read_string_until_nul_raw(&mut reader, encoding).map(|(decoded, _)| decoded)
+        };
+        // Valid UTF-8: Auto decodes it as UTF-8, Windows1252 byte by byte.
+        let utf8 = b"Caf\xC3\xA9\0";
+        assert_eq!(read(utf8, TextEncoding::Auto).unwrap(), "Café");
+        assert_eq!(read(utf8, TextEncoding::Windows1252).unwrap(), "CafÃ©");
+        assert_eq!(read(utf8, TextEncoding::Utf8).unwrap(), "Café");
+
+        // A lone Windows-1252 byte isn't UTF-8.
+        let legacy = b"Caf\xE9\0";
+        assert_eq!(read(legacy, TextEncoding::Auto).unwrap(), "Café");
+        assert!(matches!(
+            read(legacy, TextEncoding::Utf8),
+            Err(PuzError::InvalidUtf8 { .. })
+        ));
+    }
+
     /// Test reading null-terminated string with no terminator
     /// Should handle malformed files gracefully
     #[test]
@@ -262,7 +295,7 @@ mod tests {
         let data = vec![72, 101, 108, 108, 111]; // "Hello" with no null terminator
         let mut reader = BufReader::new(Cursor::new(data));
 
-        let result = read_string_until_nul_raw(&mut reader);
+        let result = read_string_until_nul_raw(&mut reader, TextEncoding::Auto);
         assert!(result.is_err());
         matches!(result.unwrap_err(), PuzError::IoError { .. });
     }
@@ -280,7 +313,9 @@ mod tests {
 
         let result = find_section(&data, "GRBS").unwrap();
         assert!(result.is_some());
-        assert_eq!(result.unwrap(), vec![0x01, 0x02, 0x03, 0x04]);
+        let section = result.unwrap();
+        assert_eq!(section.data, vec![0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(section.checksum, 0xCDAB);
     }
 
     /// Test finding non-existent sections
@@ -1,5 +1,6 @@
 use crate::{
     error::{ParseResult, PuzError, PuzWarning},
+    options::{ChecksumPolicy, ParseOptions},
     puzzle::Puzzle,
     types::*,
 };
@@ -13,25 +14,39 @@ mod io;
 mod strings;
 mod validation;
 
-use clues::process_clues;
+use clues::{process_clues, renumber_clues};
 use extensions::parse_extensions_with_recovery;
-use grids::parse_grids;
+use grids::{parse_grids, resolve_black_squares};
 use header::parse_header;
 use io::{read_remaining_data, validate_file_magic};
 pub(crate) use strings::RawStrings;
 use strings::parse_strings;
 use validation::validate_puzzle;
 
-pub(crate) fn parse_puzzle<R: Read>(reader: R) -> Result<ParseResult<Puzzle>, PuzError> {
-    parse_puzzle_inner(reader, false)
-}
-
-/// Parse and require all stored checksums to match; a mismatch is a hard error.
-pub(crate) fn parse_puzzle_strict<R: Read>(reader: R) -> Result<ParseResult<Puzzle>, PuzError> {
-    parse_puzzle_inner(reader, true)
+/// Parse a puzzle, handling each kind of problem as `options` says.
+pub(crate) fn parse_puzzle<R: Read>(
+    reader: R,
+    options: &ParseOptions,
+) -> Result<ParseResult<Puzzle>, PuzError> {
+    let Some(limit) = options.max_file_size else {
+        return parse_puzzle_inner(reader, options);
+    };
+    // Read at most one byte past the limit: enough to tell the input is too
+    // long without buffering all of it.
+    let mut data = Vec::new();
+    reader
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut data)?;
+    if data.len() as u64 > limit {
+        return Err(PuzError::FileTooLarge { limit });
+    }
+    parse_puzzle_inner(data.as_slice(), options)
 }
 
-fn parse_puzzle_inner<R: Read>(reader: R, strict: bool) -> Result<ParseResult<Puzzle>, PuzError> {
+fn parse_puzzle_inner<R: Read>(
+    reader: R,
+    options: &ParseOptions,
+) -> Result<ParseResult<Puzzle>, PuzError> {
     let mut buf_reader = BufReader::new(reader);
     let mut warnings = Vec::new();
 
@@ -53,19 +68,33 @@ fn parse_puzzle_inner<R: Read>(reader: R, strict: bool) -> Result<ParseResult<Pu
     let bitmask = header.bitmask;
     let scrambled_tag = header.scrambled_tag;
 
-    let (grids, is_diagramless) = parse_grids(&mut buf_reader, header.width, header.height)?;
+    let (mut grids, is_diagramless) = parse_grids(&mut buf_reader, header.width, header.height)?;
+    // The checksums cover the grids as stored, before any repair.
+    let stored_grids = grids.clone();
+    warnings.extend(resolve_black_squares(&mut grids, options.black_squares)?);
 
-    let strings = parse_strings(&mut buf_reader, header.num_clues)?;
+    let strings = parse_strings(&mut buf_reader, header.num_clues, options.encoding)?;
 
     let extra_data = read_remaining_data(&mut buf_reader)?;
     let (extensions, ext_warnings) =
-        parse_extensions_with_recovery(&extra_data, header.width, header.height)?;
+        parse_extensions_with_recovery(&extra_data, header.width, header.height, options)?;
     warnings.extend(ext_warnings);
 
-    let (clues, clue_warning) = process_clues(&grids.blank, &strings.clues)?;
+    // The clue list was written for the blank grid as stored, so number it
+    // there, then carry the clues over to the repaired grid if it differs.
+    let (clues, clue_warning) =
+        process_clues(&stored_grids.blank, &strings.clues, options.extra_clues)?;
     if let Some(w) = clue_warning {
         warnings.push(w);
     }
+    let ordered_clues = crate::grid::order_clues(&stored_grids.blank, &clues)?;
+    let clues = if grids.blank == stored_grids.blank {
+        clues
+    } else {
+        let (clues, clue_warnings) = renumber_clues(&stored_grids.blank, &grids.blank, clues);
+        warnings.extend(clue_warnings);
+        clues
+    };
 
     let raw_strings = strings.raw;
     let puzzle = Puzzle {
@@ -90,13 +119,17 @@ fn parse_puzzle_inner<R: Read>(reader: R, strict: bool) -> Result<ParseResult<Pu
     // Warn about non-standard solution characters that no rebus entry explains.
     warnings.extend(validation::check_unbacked_grid_chars(&puzzle));
 
-    // Checksum validation: reconstruct the clue order and recompute checksums
-    // independently of the writer, then compare with the stored values. When
-    // available, the raw string bytes make the text checksum byte-faithful.
-    let ordered_clues = crate::grid::order_clues(&puzzle.grid.blank, &puzzle.clues)?;
+    if options.checksums == ChecksumPolicy::Ignore {
+        return Ok(ParseResult::with_warnings(puzzle, warnings));
+    }
+
+    // Checksum validation: recompute checksums from the grids and clue order
+    // as stored, independently of the writer, then compare with the stored
+    // values. When available, the raw string bytes make the text checksum
+    // byte-faithful.
     match crate::checksums::verify(
         &puzzle.info,
-        &puzzle.grid,
+        &stored_grids,
         &ordered_clues,
         &raw_strings,
         bitmask,
@@ -105,7 +138,7 @@ fn parse_puzzle_inner<R: Read>(reader: R, strict: bool) -> Result<ParseResult<Pu
     ) {
         Ok(()) => {}
         Err(e) => {
-            if strict {
+            if options.checksums == ChecksumPolicy::Error {
                 return Err(e);
             }
             if let PuzError::InvalidChecksum {
@@ -1,5 +1,6 @@
 use super::io::read_string_until_nul_raw;
 use crate::error::PuzError;
+use crate::options::TextEncoding;
 use std::io::{BufReader, Read};
 
 #[derive(Debug)]
@@ -29,6 +30,7 @@ pub(crate) struct RawStrings {
 pub(crate) fn parse_strings<R: Read>(
     reader: &mut BufReader<R>,
     num_clues: u16,
+    encoding: TextEncoding,
 ) -> Result<StringData, PuzError> {
     // String data format (after grid data):
     // See: https://github.com/mwln/puz.rs/blob/main/PUZ.md
@@ -40,14 +42,14 @@ pub(crate) fn parse_strings<R: Read>(
     // 4. Clues (num_clues null-terminated strings, in reading order)
     // 5. Notes (null-terminated)
 
-    let (title, title_raw) = read_string_until_nul_raw(reader)?;
-    let (author, author_raw) = read_string_until_nul_raw(reader)?;
-    let (copyright, copyright_raw) = read_string_until_nul_raw(reader)?;
+    let (title, title_raw) = read_string_until_nul_raw(reader, encoding)?;
+    let (author, author_raw) = read_string_until_nul_raw(reader, encoding)?;
+    let (copyright, copyright_raw) = read_string_until_nul_raw(reader, encoding)?;
 
     let mut clues = Vec::with_capacity(num_clues as usize);
     let mut clues_raw = Vec::with_capacity(num_clues as usize);
     for i in 0..num_clues {
-        match read_string_until_nul_raw(reader) {
+        match read_string_until_nul_raw(reader, encoding) {
             Ok((clue, raw)) => {
                 clues.push(clue);
                 clues_raw.push(raw);
@@ -61,7 +63,7 @@ pub(crate) fn parse_strings<R: Read>(
         }
     }
 
-    let (notes, notes_raw) = read_string_until_nul_raw(reader)?;
+    let (notes, notes_raw) = read_string_until_nul_raw(reader, encoding)?;
 
     if clues.len() != num_clues as usize {
         return Err(PuzError::InvalidClueCount {
@@ -150,6 +150,7 @@ mod tests {
                 rebus: None,
                 circles: None,
                 given: None,
+                timer: None,
             },
         }
     }
@@ -382,6 +383,7 @@ mod tests {
                 rebus,
                 circles: None,
                 given: None,
+                timer: None,
             },
         }
     }
@@ -3,7 +3,8 @@ use std::path::Path;
 
 use crate::error::{ParseResult, PuzError};
 use crate::grid::{FREE_SQUARE, TAKEN_SQUARE, cell_needs_across_clue, cell_needs_down_clue};
-use crate::types::{ClueAnswer, Clues, Direction, Extensions, Grid, PuzzleInfo};
+use crate::options::{ChecksumPolicy, ParseOptions};
+use crate::types::{ClueAnswer, Clues, Direction, Extensions, Grid, PuzzleInfo, Rebus};
 
 /// A complete crossword puzzle.
 ///
@@ -94,6 +95,7 @@ impl Puzzle {
                 rebus: None,
                 circles: None,
                 given: None,
+                timer: None,
             },
         }
     }
@@ -134,7 +136,8 @@ impl Puzzle {
 
     /// Begin a configurable parse.
     ///
-    /// Set options such as [`PuzzleReader::strict`], then call a terminal
+    /// Set options such as [`PuzzleReader::strict`] or
+    /// [`PuzzleReader::options`], then call a terminal
     /// method ([`PuzzleReader::from_file`], [`PuzzleReader::from_bytes`],
     /// [`PuzzleReader::from_reader`], or their `*_verbose` variants that also
     /// return parse warnings).
@@ -268,13 +271,48 @@ impl Puzzle {
         self
     }
 
+    /// Mark circled squares, one `bool` per cell in row-major rows.
+    ///
+    /// Written to `.puz` as the GEXT section. The grid must match the puzzle's
+    /// dimensions; a mismatch is caught when the puzzle is written.
+    #[must_use]
+    pub fn circles(mut self, circles: Vec<Vec<bool>>) -> Self {
+        self.extensions.circles = Some(circles);
+        self
+    }
+
+    /// Set the rebus squares (cells holding more than one letter).
+    ///
+    /// The solution grid keeps one character in each rebus cell, typically
+    /// the first letter of its rebus text. Written to `.puz` as the GRBS and
+    /// RTBL sections.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use puz_parse::{Puzzle, Rebus};
+    /// use std::collections::HashMap;
+    ///
+    /// let puzzle = Puzzle::new().grid(["HA", "AT"]).rebus(Rebus {
+    ///     grid: vec![vec![1, 0], vec![0, 0]],
+    ///     table: HashMap::from([(0, "HEART".to_string())]),
+    /// });
+    /// assert_eq!(puzzle.extensions.rebus.unwrap().get(0, 0), Some("HEART"));
+    /// ```
+    #[must_use]
+    pub fn rebus(mut self, rebus: Rebus) -> Self {
+        self.extensions.rebus = Some(rebus);
+        self
+    }
+
     /// Pair every clue with the answer read from the solution grid.
     ///
     /// Walks the grid in reading order. For each numbered cell, an across entry
     /// reads solution cells rightward until a black square or the grid edge; a
     /// down entry reads downward the same way. Each entry is matched with its
     /// clue text from [`Clues::across`] / [`Clues::down`]. Entries are returned
-    /// in reading order (across before down at the same number).
+    /// in reading order (across before down at the same number), and each
+    /// records the row and column of its numbered cell.
     ///
     /// The answer characters are taken from the solution grid as-is, so a rebus
     /// or theme cell contributes whatever character the grid stores there.
@@ -324,6 +362,8 @@ impl Puzzle {
                     out.push(ClueAnswer {
                         direction: Direction::Across,
                         number,
+                        row,
+                        col,
                         clue: self.clues.across.get(number).unwrap_or("").to_string(),
                         answer,
                     });
@@ -342,6 +382,8 @@ impl Puzzle {
                     out.push(ClueAnswer {
                         direction: Direction::Down,
                         number,
+                        row,
+                        col,
                         clue: self.clues.down.get(number).unwrap_or("").to_string(),
                         answer,
                     });
@@ -369,30 +411,50 @@ impl Default for Puzzle {
 /// parse warnings.
 #[derive(Debug, Clone, Default)]
 pub struct PuzzleReader {
-    strict: bool,
+    options: ParseOptions,
 }
 
 impl PuzzleReader {
     fn new() -> Self {
-        Self { strict: false }
+        Self::default()
+    }
+
+    /// Handle each kind of problem as `options` says, replacing any options
+    /// set before.
+    ///
+    /// ```rust,no_run
+    /// use puz_parse::{BlackSquarePolicy, ParseOptions, Puzzle};
+    ///
+    /// let options = ParseOptions::new().black_squares(BlackSquarePolicy::PreferBlank);
+    /// let parsed = Puzzle::reader().options(options).from_file_verbose("puzzle.puz")?;
+    /// # Ok::<(), puz_parse::PuzError>(())
+    /// ```
+    #[must_use]
+    pub fn options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
     }
 
     /// Require all stored checksums to match.
     ///
     /// When `true`, a checksum mismatch is returned as
     /// [`PuzError::InvalidChecksum`] instead of being recorded as a warning.
+    /// This is shorthand for setting [`ParseOptions::checksums`] to
+    /// [`ChecksumPolicy::Error`] (or, when `false`, [`ChecksumPolicy::Warn`]);
+    /// the other options are kept.
     #[must_use]
     pub fn strict(mut self, strict: bool) -> Self {
-        self.strict = strict;
+        let policy = if strict {
+            ChecksumPolicy::Error
+        } else {
+            ChecksumPolicy::Warn
+        };
+        self.options = self.options.checksums(policy);
         self
     }
 
     fn parse<R: Read>(&self, reader: R) -> Result<ParseResult<Puzzle>, PuzError> {
-        if self.strict {
-            crate::parser::parse_puzzle_strict(reader)
-        } else {
-            crate::parser::parse_puzzle(reader)
-        }
+        crate::parser::parse_puzzle(reader, &self.options)
     }
 
     fn open(path: &Path) -> Result<std::fs::File, PuzError> {
@@ -471,6 +533,8 @@ fn generate_placeholder_clues(blank: &[String]) -> Clues {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::PuzWarning;
+    use crate::options::{BlackSquarePolicy, ExtensionPolicy, ExtraCluePolicy};
 
     #[test]
     fn test_new_is_empty() {
@@ -536,6 +600,20 @@ mod tests {
         assert_eq!(puzzle.info.width, 2);
     }
 
+    #[test]
+    fn test_circles_and_rebus_round_trip() {
+        let p = Puzzle::new()
+            .grid(["HA", "AT"])
+            .circles(vec![vec![false, true], vec![false, false]])
+            .rebus(Rebus {
+                grid: vec![vec![1, 0], vec![0, 0]],
+                table: std::collections::HashMap::from([(0, "HEART".to_string())]),
+            });
+        let parsed = Puzzle::from_bytes(&crate::to_bytes(&p).unwrap()).unwrap();
+        assert_eq!(parsed.extensions.circles, p.extensions.circles);
+        assert_eq!(parsed.extensions.rebus, p.extensions.rebus);
+    }
+
     #[test]
     fn test_from_bytes_round_trip() {
         let p = Puzzle::new().title("T").author("A").grid(["AB", "CD"]);
@@ -574,6 +652,179 @@ mod tests {
         assert!(matches!(err, PuzError::InvalidChecksum { .. }));
     }
 
+    #[test]
+    fn test_reader_options_ignore_checksums() {
+        let p = Puzzle::new().title("T").author("A").grid(["AB", "CD"]);
+        let mut bytes = crate::to_bytes(&p).unwrap();
+        bytes[0] ^= 0xFF;
+
+        let options = ParseOptions::new().checksums(ChecksumPolicy::Ignore);
+        let parsed = Puzzle::reader()
+            .options(options)
+            .from_bytes_verbose(&bytes)
+            .unwrap();
+        assert!(parsed.warnings.is_empty(), "got: {:?}", parsed.warnings);
+    }
+
+    #[test]
+    fn test_reader_max_file_size() {
+        let p = Puzzle::new().title("T").author("A").grid(["AB", "CD"]);
+        let bytes = crate::to_bytes(&p).unwrap();
+        let len = bytes.len() as u64;
+
+        let options = ParseOptions::new().max_file_size(len);
+        assert!(Puzzle::reader().options(options).from_bytes(&bytes).is_ok());
+
+        // `strict` changes only the checksum policy, so the limit still holds.
+        let options = ParseOptions::new().max_file_size(len - 1);
+        let err = Puzzle::reader()
+            .options(options)
+            .strict(true)
+            .from_bytes(&bytes)
+            .unwrap_err();
+        assert_eq!(err, PuzError::FileTooLarge { limit: len - 1 });
+    }
+
+    /// A 3x3 file whose blank grid blacks out the center square while the
+    /// solution has an `E` there, with the clues the stored blank grid takes:
+    /// 1A, 1D, 2D down the right column, and 3A along the bottom row.
+    fn black_square_mismatch() -> Vec<u8> {
+        let mut p = Puzzle::new().grid(["ABC", "DEF", "GHI"]);
+        p.grid.blank = ["---", "-.-", "---"].map(String::from).to_vec();
+        p.clues = Clues::default();
+        p.clues.across.set(1, "top");
+        p.clues.across.set(3, "bottom");
+        p.clues.down.set(1, "left");
+        p.clues.down.set(2, "right");
+        crate::to_bytes(&p).unwrap()
+    }
+
+    fn parse_with(bytes: &[u8], options: ParseOptions) -> Result<ParseResult<Puzzle>, PuzError> {
+        Puzzle::reader().options(options).from_bytes_verbose(bytes)
+    }
+
+    fn recoveries(warnings: &[PuzWarning]) -> usize {
+        warnings
+            .iter()
+            .filter(|w| matches!(w, PuzWarning::DataRecovery { .. }))
+            .count()
+    }
+
+    #[test]
+    fn test_black_square_policy_error() {
+        let err = Puzzle::from_bytes(&black_square_mismatch()).unwrap_err();
+        assert!(matches!(err, PuzError::InvalidGrid { .. }));
+    }
+
+    #[test]
+    fn test_black_square_policy_prefer_solution() {
+        let options = ParseOptions::new().black_squares(BlackSquarePolicy::PreferSolution);
+        let parsed = parse_with(&black_square_mismatch(), options).unwrap();
+        let p = parsed.result;
+        assert_eq!(p.grid.blank, vec!["---", "---", "---"]);
+
+        // Opening the center starts words down the middle column and across
+        // the middle row. The clues stay with their words, which renumbers
+        // them: the right column is now 3D and the bottom row 5A.
+        assert_eq!(p.clues.across.get(1), Some("top"));
+        assert_eq!(p.clues.across.get(4), Some(""));
+        assert_eq!(p.clues.across.get(5), Some("bottom"));
+        assert_eq!(p.clues.down.get(2), Some(""));
+        assert_eq!(p.clues.down.get(3), Some("right"));
+
+        // One warning for the square, one for each unclued word, and none for
+        // checksums: they are checked against the grids as stored.
+        assert_eq!(recoveries(&parsed.warnings), 3);
+        assert_eq!(parsed.warnings.len(), 3, "got: {:?}", parsed.warnings);
+        let strict = ParseOptions::strict().black_squares(BlackSquarePolicy::PreferSolution);
+        assert!(parse_with(&black_square_mismatch(), strict).is_ok());
+    }
+
+    #[test]
+    fn test_black_square_policy_prefer_blank() {
+        let options = ParseOptions::strict().black_squares(BlackSquarePolicy::PreferBlank);
+        let parsed = parse_with(&black_square_mismatch(), options).unwrap();
+        let p = parsed.result;
+        assert_eq!(p.grid.solution, vec!["ABC", "D.F", "GHI"]);
+        assert_eq!(p.clues.across.get(3), Some("bottom"));
+        assert_eq!(parsed.warnings.len(), 1, "got: {:?}", parsed.warnings);
+        assert_eq!(recoveries(&parsed.warnings), 1);
+    }
+
+    /// A 2x2 file with a fifth clue string stored before the notes. Its
+    /// checksums don't cover the extra string, so they are ignored here.
+    fn extra_clue() -> Vec<u8> {
+        let p = Puzzle::new().grid(["AB", "CD"]);
+        let mut bytes = crate::to_bytes(&p).unwrap();
+        let notes = crate::raw::strings_end(&bytes).unwrap() - 1;
+        bytes.splice(notes..notes, *b"EXTRA\0");
+        bytes[0x2E] += 1;
+        bytes
+    }
+
+    #[test]
+    fn test_extra_clue_policy_warn() {
+        let options = ParseOptions::new().checksums(ChecksumPolicy::Ignore);
+        let parsed = parse_with(&extra_clue(), options).unwrap();
+        assert_eq!(parsed.result.clues.raw.len(), 5);
+        assert_eq!(
+            parsed.warnings,
+            vec![PuzWarning::ExtraClues {
+                slots: 4,
+                provided: 5
+            }]
+        );
+    }
+
+    #[test]
+    fn test_extra_clue_policy_error() {
+        let options = ParseOptions::new()
+            .checksums(ChecksumPolicy::Ignore)
+            .extra_clues(ExtraCluePolicy::Error);
+        let err = parse_with(&extra_clue(), options).unwrap_err();
+        assert!(matches!(err, PuzError::InvalidClueCount { found: 5, .. }));
+    }
+
+    #[test]
+    fn test_extra_clue_policy_truncate() {
+        let options = ParseOptions::new()
+            .checksums(ChecksumPolicy::Ignore)
+            .extra_clues(ExtraCluePolicy::Truncate);
+        let parsed = parse_with(&extra_clue(), options).unwrap();
+        assert_eq!(parsed.result.clues.raw.len(), 4);
+        assert!(parsed.warnings.is_empty(), "got: {:?}", parsed.warnings);
+    }
+
+    /// A 2x2 file with a GEXT section too short for its grid.
+    fn short_extension() -> Vec<u8> {
+        let p = Puzzle::new().grid(["AB", "CD"]);
+        let mut bytes = crate::to_bytes(&p).unwrap();
+        let data = [0x80, 0];
+        bytes.extend_from_slice(b"GEXT");
+        bytes.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(&crate::checksums::cksum_region(&data, 0).to_le_bytes());
+        bytes.extend_from_slice(&data);
+        bytes.push(0);
+        bytes
+    }
+
+    #[test]
+    fn test_extension_policy_skip() {
+        let parsed = parse_with(&short_extension(), ParseOptions::new()).unwrap();
+        assert!(parsed.result.extensions.circles.is_none());
+        assert!(matches!(
+            &parsed.warnings[..],
+            [PuzWarning::SkippedExtension { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_extension_policy_error() {
+        let options = ParseOptions::new().extensions(ExtensionPolicy::Error);
+        let err = parse_with(&short_extension(), options).unwrap_err();
+        assert!(matches!(err, PuzError::ParseError { context, .. } if context == "GEXT"));
+    }
+
     #[test]
     fn test_clue_answers_reads_solution_letters() {
         // 2x2 open grid. Slots: 1A "AB", 1D "AC", 2D "BD", 3A "CD".
@@ -620,4 +871,24 @@ mod tests {
         assert_eq!(entries[1].direction, Direction::Down);
         assert_eq!(entries[1].number, 1);
     }
+
+    #[test]
+    fn test_clue_answers_record_start_cell() {
+        // "AB." / "CDE": 1A and 1D start at (0,0), 2D at (0,1), 3A at (1,0).
+        let p = Puzzle::new().grid(["AB.", "CDE"]);
+        let starts: Vec<(Direction, u16, usize, usize)> = p
+            .clue_answers()
+            .iter()
+            .map(|e| (e.direction, e.number, e.row, e.col))
+            .collect();
+        assert_eq!(
+            starts,
+            vec![
+                (Direction::Across, 1, 0, 0),
+                (Direction::Down, 1, 0, 0),
+                (Direction::Down, 2, 0, 1),
+                (Direction::Across, 3, 1, 0),
+            ]
+        );
+    }
 }
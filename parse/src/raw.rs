@@ -93,6 +93,32 @@ pub struct RawSection {
     pub data: Vec<u8>,
 }
 
+impl RawSection {
+    /// The checksum of the section's data, as a writer would store it.
+    pub fn computed_checksum(&self) -> u16 {
+        crate::checksums::cksum_region(&self.data, 0)
+    }
+
+    /// Whether the stored checksum matches the data. A section cut off by the
+    /// end of the file never matches.
+    ///
+    /// ```
+    /// use puz_parse::raw::RawSection;
+    ///
+    /// let section = RawSection {
+    ///     tag: "GEXT".into(),
+    ///     offset: 0,
+    ///     length: 1,
+    ///     checksum: 0x0080,
+    ///     data: vec![0x80],
+    /// };
+    /// assert!(section.checksum_ok());
+    /// ```
+    pub fn checksum_ok(&self) -> bool {
+        self.data.len() == self.length && self.computed_checksum() == self.checksum
+    }
+}
+
 /// Section tags this module recognizes when scanning.
 const KNOWN_SECTIONS: [&[u8; 4]; 6] = [b"GRBS", b"RTBL", b"GEXT", b"LTIM", b"RUSR", b"MARK"];
 
@@ -269,6 +295,21 @@ pub fn read_strings(data: &[u8]) -> Option<RawStrings> {
     })
 }
 
+/// The offset just past the notes' NUL terminator, where the extension
+/// sections begin. Returns `None` if the string table runs off the end of the
+/// data.
+pub(crate) fn strings_end(data: &[u8]) -> Option<usize> {
+    let header = read_header(data)?;
+    let board = (header.width as usize) * (header.height as usize);
+    let mut offset = HEADER_LEN + 2 * board;
+    // Title, author, copyright, the clues, then notes.
+    for _ in 0..header.num_clues as usize + 4 {
+        let nul = data.get(offset..)?.iter().position(|&b| b == 0)?;
+        offset += nul + 1;
+    }
+    Some(offset)
+}
+
 /// Read a NUL-terminated string starting at `*offset`, advancing past the NUL.
 ///
 /// Bytes are decoded lossily as UTF-8; a missing terminator reads to the end.
@@ -314,6 +355,177 @@ pub fn scan_sections(data: &[u8]) -> Vec<RawSection> {
     sections
 }
 
+/// A labeled range of bytes in a `.puz` file, from [`layout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// Byte offset of the range's first byte.
+    pub offset: usize,
+    /// Number of bytes in the range.
+    pub len: usize,
+    /// What the bytes are, e.g. `width = 15`, `clue 12`, or `GEXT data`.
+    pub label: String,
+    /// Whether the bytes are outside every structure [`layout`] knows.
+    pub unknown: bool,
+}
+
+/// The header fields, as `(offset, length, name)`, from the header table in
+/// `PUZ.md`.
+const HEADER_FIELDS: [(usize, usize, &str); 14] = [
+    (0x00, 2, "overall checksum"),
+    (0x02, 12, "file magic"),
+    (0x0E, 2, "CIB checksum"),
+    (0x10, 4, "masked low checksums"),
+    (0x14, 4, "masked high checksums"),
+    (OFF_VERSION, 4, "version string"),
+    (0x1C, 2, "reserved"),
+    (0x1E, 2, "scrambled checksum"),
+    (0x20, 12, "reserved"),
+    (OFF_WIDTH, 1, "width"),
+    (OFF_HEIGHT, 1, "height"),
+    (OFF_NUM_CLUES, 2, "number of clues"),
+    (OFF_BITMASK, 2, "bitmask"),
+    (OFF_SCRAMBLED, 2, "scrambled tag"),
+];
+
+/// Label every byte of the file with the structure it belongs to.
+///
+/// The regions are in file order and cover the file exactly: the header
+/// fields, the solution and blank grids, each string of the string table
+/// (`clue N` counts from 1, in file order), and each extension section's tag,
+/// length, checksum, data, and NUL terminator. Bytes that belong to none of
+/// those, such as an unrecognized section or trailing garbage, are regions
+/// marked [`unknown`](Region::unknown). A structure cut short by the end of
+/// the file has `(truncated)` in its label.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use puz_parse::raw;
+///
+/// let data = std::fs::read("puzzle.puz")?;
+/// for region in raw::layout(&data) {
+///     println!("{:#06x} {:>5} {}", region.offset, region.len, region.label);
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn layout(data: &[u8]) -> Vec<Region> {
+    let mut layout = Layout {
+        data,
+        regions: Vec::new(),
+        pos: 0,
+    };
+
+    for (offset, len, name) in HEADER_FIELDS {
+        // Counts are shown in decimal; checksums and flags in hex.
+        let label = match (offset + len <= data.len(), len) {
+            (true, 1) => format!("{name} = {}", data[offset]),
+            (true, 2) if offset == OFF_NUM_CLUES => {
+                format!("{name} = {}", u16le(data, offset))
+            }
+            (true, 2) => format!("{name} = 0x{:04X}", u16le(data, offset)),
+            _ => name.to_string(),
+        };
+        layout.add(offset, len, label);
+    }
+    let Some(header) = read_header(data) else {
+        return layout.finish();
+    };
+
+    let board = header.width as usize * header.height as usize;
+    layout.add(HEADER_LEN, board, "solution grid".to_string());
+    layout.add(HEADER_LEN + board, board, "blank grid".to_string());
+
+    let names = ["title", "author", "copyright"]
+        .into_iter()
+        .map(str::to_string)
+        .chain((1..=header.num_clues).map(|i| format!("clue {i}")))
+        .chain(["notes".to_string()]);
+    let mut offset = HEADER_LEN + 2 * board;
+    for name in names {
+        if offset >= data.len() {
+            break;
+        }
+        // Each string runs through its NUL terminator.
+        let len = data[offset..]
+            .iter()
+            .position(|&b| b == 0)
+            .map_or(data.len() - offset, |nul| nul + 1);
+        layout.add(offset, len, name);
+        offset += len;
+    }
+
+    // Only scan past the strings, so a tag-like run of letters in a grid or
+    // clue isn't taken for a section.
+    let start = layout.pos;
+    for section in scan_sections(&data[start..]) {
+        let at = start + section.offset;
+        let tag = &section.tag;
+        layout.add(at, 4, format!("{tag} tag"));
+        layout.add(at + 4, 2, format!("{tag} length = {}", section.length));
+        layout.add(
+            at + 6,
+            2,
+            format!("{tag} checksum = 0x{:04X}", section.checksum),
+        );
+        layout.add(at + 8, section.length, format!("{tag} data"));
+        let end = at + 8 + section.length;
+        if data.get(end) == Some(&0) {
+            layout.add(end, 1, format!("{tag} terminator"));
+        }
+    }
+    layout.finish()
+}
+
+/// Builds [`layout`]'s regions in file order, filling gaps with unknown ones.
+struct Layout<'a> {
+    data: &'a [u8],
+    regions: Vec<Region>,
+    /// The end of the last region.
+    pos: usize,
+}
+
+impl Layout<'_> {
+    /// Add a region, clamped to the end of the file. Regions must be added in
+    /// file order; one overlapping an earlier region is dropped.
+    fn add(&mut self, offset: usize, len: usize, mut label: String) {
+        if offset < self.pos {
+            return;
+        }
+        self.unknown_until(offset.min(self.data.len()));
+        let end = offset.saturating_add(len).min(self.data.len());
+        if end <= offset {
+            return;
+        }
+        if end - offset < len {
+            label.push_str(" (truncated)");
+        }
+        self.regions.push(Region {
+            offset,
+            len: end - offset,
+            label,
+            unknown: false,
+        });
+        self.pos = end;
+    }
+
+    fn unknown_until(&mut self, end: usize) {
+        if end > self.pos {
+            self.regions.push(Region {
+                offset: self.pos,
+                len: end - self.pos,
+                label: "unknown".to_string(),
+                unknown: true,
+            });
+            self.pos = end;
+        }
+    }
+
+    fn finish(mut self) -> Vec<Region> {
+        self.unknown_until(self.data.len());
+        self.regions
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -481,6 +693,22 @@ mod tests {
         assert_eq!(gext.length, 4);
         assert_eq!(gext.checksum, 0xABCD);
         assert_eq!(gext.data, vec![0x80, 0, 0, 0]);
+        assert!(!gext.checksum_ok());
+    }
+
+    #[test]
+    fn test_scan_sections_checksum_ok() {
+        let mut data = build(2, 2, b"AB.D", b"--.-", &["a"], 0x0001);
+        let payload = [0x80, 0, 0, 0x80];
+        let cksum = crate::checksums::cksum_region(&payload, 0);
+        data.extend_from_slice(b"GEXT");
+        data.extend_from_slice(&4u16.to_le_bytes());
+        data.extend_from_slice(&cksum.to_le_bytes());
+        data.extend_from_slice(&payload);
+
+        let sections = scan_sections(&data);
+        assert_eq!(sections[0].computed_checksum(), cksum);
+        assert!(sections[0].checksum_ok());
     }
 
     #[test]
@@ -499,5 +727,64 @@ mod tests {
             .expect("GRBS found");
         assert_eq!(grbs.length, 9999);
         assert_eq!(grbs.data.len(), 2, "data must be clamped to file end");
+        assert!(!grbs.checksum_ok());
+    }
+
+    #[test]
+    fn test_layout_covers_the_file_in_order() {
+        let mut data = build(2, 2, b"AB.D", b"--.-", &["one", "two"], 0x0001);
+        data.extend_from_slice(b"GEXT");
+        data.extend_from_slice(&4u16.to_le_bytes());
+        data.extend_from_slice(&0xABCDu16.to_le_bytes());
+        data.extend_from_slice(&[0x80, 0, 0, 0, 0]);
+
+        let regions = layout(&data);
+        let mut pos = 0;
+        for region in &regions {
+            assert_eq!(region.offset, pos);
+            pos += region.len;
+        }
+        assert_eq!(pos, data.len());
+        assert!(regions.iter().all(|r| !r.unknown));
+
+        let labels: Vec<&str> = regions.iter().map(|r| r.label.as_str()).collect();
+        assert_eq!(labels[9], "width = 2");
+        assert_eq!(labels[11], "number of clues = 2");
+        assert_eq!(
+            labels[14..],
+            [
+                "solution grid",
+                "blank grid",
+                "title",
+                "author",
+                "copyright",
+                "clue 1",
+                "clue 2",
+                "notes",
+                "GEXT tag",
+                "GEXT length = 4",
+                "GEXT checksum = 0xABCD",
+                "GEXT data",
+                "GEXT terminator",
+            ]
+        );
+        let clue = regions.iter().find(|r| r.label == "clue 2").unwrap();
+        assert_eq!(&data[clue.offset..clue.offset + clue.len], b"two\0");
+    }
+
+    #[test]
+    fn test_layout_marks_unknown_and_truncated_bytes() {
+        let mut data = build(2, 2, b"AB.D", b"--.-", &["a"], 0x0001);
+        let strings_end = data.len();
+        data.extend_from_slice(b"JUNK");
+        let regions = layout(&data);
+        let last = regions.last().unwrap();
+        assert!(last.unknown);
+        assert_eq!((last.offset, last.len), (strings_end, 4));
+
+        let regions = layout(&data[..HEADER_LEN + 3]);
+        let grid = regions.last().unwrap();
+        assert_eq!(grid.label, "solution grid (truncated)");
+        assert_eq!(grid.len, 3);
     }
 }
@@ -65,6 +65,10 @@ pub struct ClueAnswer {
     pub direction: Direction,
     /// The clue number.
     pub number: u16,
+    /// Zero-based row of the entry's first cell (the numbered cell).
+    pub row: usize,
+    /// Zero-based column of the entry's first cell (the numbered cell).
+    pub col: usize,
     /// The clue text.
     pub clue: String,
     /// The answer, read from the solution grid for this slot.
@@ -300,6 +304,21 @@ pub struct Extensions {
     pub circles: Option<Vec<Vec<bool>>>,
     /// Grid indicating which squares were given to the solver, if any
     pub given: Option<Vec<Vec<bool>>>,
+    /// The solving timer saved with the puzzle (the LTIM section), if any
+    pub timer: Option<Timer>,
+}
+
+/// A solver's saved timer, from the LTIM extension section.
+///
+/// Solving programs store the time spent so far so a partly solved puzzle can
+/// be resumed with its clock intact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timer {
+    /// Seconds elapsed on the clock
+    pub elapsed_secs: u32,
+    /// Whether the clock was running when the file was saved
+    pub running: bool,
 }
 
 /// Rebus information for squares containing multiple letters.
@@ -316,10 +335,39 @@ pub struct Rebus {
     pub table: HashMap<u8, String>,
 }
 
+impl Rebus {
+    /// The rebus text at a cell, or `None` if the cell is not a rebus square.
+    ///
+    /// Grid values are 1-based while table keys are 0-based: grid value `n`
+    /// names table key `n - 1`. This does that lookup so callers don't have
+    /// to remember the offset.
+    pub fn get(&self, row: usize, col: usize) -> Option<&str> {
+        let value = *self.grid.get(row)?.get(col)?;
+        if value == 0 {
+            return None;
+        }
+        self.table.get(&(value - 1)).map(String::as_str)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_rebus_get_offsets_grid_value() {
+        let rebus = Rebus {
+            grid: vec![vec![0, 1], vec![2, 3]],
+            table: HashMap::from([(0, "HEART".to_string()), (1, "STAR".to_string())]),
+        };
+        assert_eq!(rebus.get(0, 0), None);
+        assert_eq!(rebus.get(0, 1), Some("HEART"));
+        assert_eq!(rebus.get(1, 0), Some("STAR"));
+        // A grid value with no table entry, and a cell off the grid.
+        assert_eq!(rebus.get(1, 1), None);
+        assert_eq!(rebus.get(5, 5), None);
+    }
+
     #[test]
     fn test_set_and_get() {
         let mut clues = Clues::default();
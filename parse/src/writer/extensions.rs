@@ -5,7 +5,8 @@ const GEXT_CIRCLED: u8 = 0x80;
 const GEXT_GIVEN: u8 = 0x40;
 
 /// Serialize the extension sections implied by `extensions`, in the order the
-/// parser looks for them: GRBS + RTBL (rebus), then GEXT (circles/given).
+/// parser looks for them: GRBS + RTBL (rebus), LTIM (timer), then GEXT
+/// (circles/given).
 ///
 /// Each section is framed as: 4-byte ASCII name, 2-byte little-endian data
 /// length, 2-byte data checksum, the data, and a trailing NUL byte. Only
@@ -35,6 +36,16 @@ pub(crate) fn serialize_extensions(
         write_section(&mut out, b"RTBL", rtbl.as_bytes());
     }
 
+    // LTIM: "<seconds>,<state>", state 0 = running, 1 = stopped.
+    if let Some(timer) = &extensions.timer {
+        let ltim = format!(
+            "{},{}",
+            timer.elapsed_secs,
+            if timer.running { 0 } else { 1 }
+        );
+        write_section(&mut out, b"LTIM", ltim.as_bytes());
+    }
+
     // GEXT: width*height bitmask bytes; emit if any circle/given is set.
     if extensions.circles.is_some() || extensions.given.is_some() {
         let gext = build_gext(extensions, width, height)?;
@@ -45,7 +56,7 @@ pub(crate) fn serialize_extensions(
 }
 
 /// Append one framed section: name, LE length, data checksum, data, NUL.
-fn write_section(out: &mut Vec<u8>, name: &[u8; 4], data: &[u8]) {
+pub(crate) fn write_section(out: &mut Vec<u8>, name: &[u8; 4], data: &[u8]) {
     out.extend_from_slice(name);
     out.extend_from_slice(&(data.len() as u16).to_le_bytes());
     out.extend_from_slice(&cksum_region(data, 0).to_le_bytes());
@@ -107,7 +118,7 @@ fn build_gext(extensions: &Extensions, width: u8, height: u8) -> Result<Vec<u8>,
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::Rebus;
+    use crate::types::{Rebus, Timer};
     use std::collections::HashMap;
 
     fn no_ext() -> Extensions {
@@ -115,6 +126,7 @@ mod tests {
             rebus: None,
             circles: None,
             given: None,
+            timer: None,
         }
     }
 
@@ -166,6 +178,21 @@ mod tests {
         assert_eq!(&bytes[rtbl_pos..rtbl_pos + 4], b"RTBL");
     }
 
+    #[test]
+    fn test_timer_emits_ltim_before_gext() {
+        let mut e = no_ext();
+        e.circles = Some(vec![vec![true]]);
+        e.timer = Some(Timer {
+            elapsed_secs: 90,
+            running: false,
+        });
+        let bytes = serialize_extensions(&e, 1, 1).unwrap();
+        assert_eq!(&bytes[0..4], b"LTIM");
+        assert_eq!(u16::from_le_bytes([bytes[4], bytes[5]]), 4);
+        assert_eq!(&bytes[8..12], b"90,1");
+        assert_eq!(&bytes[13..17], b"GEXT");
+    }
+
     #[test]
     fn test_gext_dimension_mismatch_errors() {
         let mut e = no_ext();
@@ -8,6 +8,9 @@ use crate::{
 mod extensions;
 mod grids;
 mod header;
+mod rewrite;
+
+pub(crate) use rewrite::rewrite_puzzle;
 
 /// Serialize a puzzle into an in-memory `.puz` byte buffer.
 pub(crate) fn write_puzzle(puzzle: &Puzzle) -> Result<Vec<u8>, PuzError> {
@@ -181,6 +184,7 @@ mod tests {
                 rebus: None,
                 circles: None,
                 given: None,
+                timer: None,
             },
         }
     }
@@ -266,6 +270,18 @@ mod tests {
         assert_eq!(Puzzle::from_bytes(&bytes).unwrap(), p);
     }
 
+    #[test]
+    fn test_round_trip_with_timer_and_progress() {
+        let mut p = sample_puzzle();
+        p.grid.blank = vec!["A-".into(), "-D".into()];
+        p.extensions.timer = Some(Timer {
+            elapsed_secs: 312,
+            running: false,
+        });
+        let bytes = to_bytes(&p).unwrap();
+        assert_eq!(Puzzle::from_bytes(&bytes).unwrap(), p);
+    }
+
     #[test]
     fn test_round_trip_with_circles_and_given() {
         let mut p = sample_puzzle();
@@ -350,6 +366,7 @@ mod tests {
                 rebus: None,
                 circles: None,
                 given: None,
+                timer: None,
             },
         };
         let bytes = to_bytes(&p).unwrap();
@@ -0,0 +1,197 @@
+use super::extensions::serialize_extensions;
+use crate::{error::PuzError, puzzle::Puzzle, raw};
+
+/// Reserved header ranges the model doesn't hold. They are outside every
+/// checksum, so the original bytes can be copied into the new header as-is.
+const RESERVED: [std::ops::Range<usize>; 2] = [0x1C..0x1E, 0x20..0x2C];
+
+/// The sections the writer produces from [`Extensions`](crate::Extensions).
+const MODELED: [&str; 4] = ["GRBS", "RTBL", "LTIM", "GEXT"];
+
+/// Serialize `puzzle` as a replacement for `original`, the file it was parsed
+/// from, keeping what the model can't hold.
+///
+/// The header, grids, and strings are written from `puzzle`. The bytes after
+/// the strings are copied from `original`, so unknown sections (RUSR, MARK,
+/// vendor tags) and stray bytes survive. A modeled section is written from
+/// `puzzle` only when its content differs from what `original` parses to;
+/// otherwise its original bytes are kept too, including any flag bits the
+/// model drops. Modeled sections `original` lacks are appended.
+///
+/// Clue strings beyond the grid's entries can't be written back, so a puzzle
+/// with any in [`Clues::raw`](crate::Clues::raw) is an error rather than a
+/// silent loss.
+pub(crate) fn rewrite_puzzle(original: &[u8], puzzle: &Puzzle) -> Result<Vec<u8>, PuzError> {
+    let entries = puzzle.clues.across.len() + puzzle.clues.down.len();
+    if puzzle.clues.raw.len() > entries {
+        return Err(PuzError::UnsupportedFeature {
+            feature: format!(
+                "{} clue string(s) beyond the grid's entries",
+                puzzle.clues.raw.len() - entries
+            ),
+        });
+    }
+
+    let mut out = super::write_puzzle(puzzle)?;
+    let (width, height) = (puzzle.info.width, puzzle.info.height);
+    let written = serialize_extensions(&puzzle.extensions, width, height)?;
+    out.truncate(out.len() - written.len());
+
+    let Some(tail_start) = raw::strings_end(original) else {
+        // Nothing readable past the strings to keep.
+        out.extend_from_slice(&written);
+        return Ok(out);
+    };
+    if original.len() >= raw::HEADER_LEN {
+        for range in RESERVED {
+            out[range.clone()].copy_from_slice(&original[range]);
+        }
+    }
+
+    // What the original's modeled sections look like after a parse and write,
+    // to tell which ones `puzzle` changed.
+    let before = Puzzle::from_bytes(original)
+        .ok()
+        .and_then(|p| serialize_extensions(&p.extensions, p.info.width, p.info.height).ok())
+        .unwrap_or_default();
+    let before = raw::scan_sections(&before);
+    let after = raw::scan_sections(&written);
+    let data_of = |sections: &[raw::RawSection], tag: &str| {
+        sections
+            .iter()
+            .find(|s| s.tag == tag)
+            .map(|s| s.data.clone())
+    };
+
+    let tail = &original[tail_start..];
+    let mut pos = 0;
+    let mut seen = Vec::new();
+    for section in raw::scan_sections(tail) {
+        let tag = section.tag.as_str();
+        if !MODELED.contains(&tag) || seen.contains(&section.tag) {
+            continue;
+        }
+        seen.push(section.tag.clone());
+        let new = data_of(&after, tag);
+        if data_of(&before, tag) == new {
+            continue;
+        }
+        // Replace the framing, data, and NUL terminator (when present).
+        let mut end = section.offset + 8 + section.data.len();
+        if tail.get(end) == Some(&0) {
+            end += 1;
+        }
+        out.extend_from_slice(&tail[pos..section.offset]);
+        if let Some(data) = new {
+            super::extensions::write_section(&mut out, tag_bytes(tag), &data);
+        }
+        pos = end;
+    }
+    out.extend_from_slice(&tail[pos..]);
+
+    for section in &after {
+        if !seen.contains(&section.tag) {
+            super::extensions::write_section(&mut out, tag_bytes(&section.tag), &section.data);
+        }
+    }
+    Ok(out)
+}
+
+fn tag_bytes(tag: &str) -> &[u8; 4] {
+    tag.as_bytes()
+        .try_into()
+        .expect("modeled section tags are four bytes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Timer, to_bytes};
+
+    /// A framed section as the writer lays it out, NUL terminator included.
+    fn section(tag: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        super::super::extensions::write_section(&mut out, tag, data);
+        out
+    }
+
+    fn sample() -> Puzzle {
+        let mut p = Puzzle::new().title("T").author("A").grid(["AB", "CD"]);
+        p.clues.across.set(1, "a1");
+        p.clues.across.set(3, "a3");
+        p.clues.down.set(1, "d1");
+        p.clues.down.set(2, "d2");
+        p
+    }
+
+    #[test]
+    fn test_rewrite_keeps_unmodeled_sections() {
+        let mut original = to_bytes(&sample()).unwrap();
+        let rusr = section(b"RUSR", b"\0\0HEART\0\0");
+        original.extend_from_slice(&rusr);
+        original.extend_from_slice(b"vendor junk");
+
+        let mut puzzle = Puzzle::from_bytes(&original).unwrap();
+        puzzle.info.title = "New title".to_string();
+        let bytes = rewrite_puzzle(&original, &puzzle).unwrap();
+
+        assert!(bytes.ends_with(&[rusr.as_slice(), b"vendor junk"].concat()));
+        let sections = raw::scan_sections(&bytes);
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].tag, "RUSR");
+        assert_eq!(Puzzle::from_bytes(&bytes).unwrap().info.title, "New title");
+        crate::validate_bytes(&bytes).unwrap();
+    }
+
+    #[test]
+    fn test_rewrite_keeps_unchanged_sections_verbatim() {
+        // 0x10 marks a square previously incorrect, which the model drops.
+        let mut original = to_bytes(&sample()).unwrap();
+        let gext = section(b"GEXT", &[0x90, 0, 0, 0x10]);
+        original.extend_from_slice(&gext);
+
+        let mut puzzle = Puzzle::from_bytes(&original).unwrap();
+        puzzle.clues.across.set(1, "new");
+        let bytes = rewrite_puzzle(&original, &puzzle).unwrap();
+        assert!(bytes.ends_with(&gext));
+    }
+
+    #[test]
+    fn test_rewrite_replaces_changed_sections_and_appends_new_ones() {
+        let mut original = to_bytes(&sample()).unwrap();
+        original.extend_from_slice(&section(b"LTIM", b"10,1"));
+        original.extend_from_slice(&section(b"RUSR", b"x"));
+
+        let mut puzzle = Puzzle::from_bytes(&original).unwrap();
+        puzzle.extensions.timer = Some(Timer {
+            elapsed_secs: 99,
+            running: false,
+        });
+        puzzle.extensions.circles = Some(vec![vec![true, false], vec![false, false]]);
+        let bytes = rewrite_puzzle(&original, &puzzle).unwrap();
+
+        let tags: Vec<_> = raw::scan_sections(&bytes)
+            .into_iter()
+            .map(|s| (s.tag, s.data))
+            .collect();
+        assert_eq!(
+            tags,
+            vec![
+                ("LTIM".to_string(), b"99,1".to_vec()),
+                ("RUSR".to_string(), b"x".to_vec()),
+                ("GEXT".to_string(), vec![0x80, 0, 0, 0]),
+            ]
+        );
+        let reparsed = Puzzle::from_bytes(&bytes).unwrap();
+        assert_eq!(reparsed.extensions.timer.map(|t| t.elapsed_secs), Some(99));
+    }
+
+    #[test]
+    fn test_rewrite_refuses_extra_clue_strings() {
+        let mut puzzle = sample();
+        puzzle.clues.raw = ["a1", "d1", "d2", "a3", "meta"].map(String::from).to_vec();
+        let original = to_bytes(&sample()).unwrap();
+        let err = rewrite_puzzle(&original, &puzzle).unwrap_err();
+        assert!(matches!(err, PuzError::UnsupportedFeature { .. }));
+    }
+}